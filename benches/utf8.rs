@@ -0,0 +1,43 @@
+//! 在不同长度上直接比较`core::str::from_utf8`与`simdutf8::basic::from_utf8`，
+//! `src/utf8.rs`中的`SIMD_THRESHOLD`依据该结果选取。
+//!
+//! ```text
+//! cargo bench --features simd-utf8,std --bench utf8
+//! ```
+
+use std::{hint::black_box, str};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const TEXT: &str = "The quick brown fox jumps over the lazy dog. \
+                    Съешь же ещё этих мягких французских булок. \
+                    いろはにほへと ちりぬるを わかよたれそ つねならむ 🦀";
+
+fn input(len: usize) -> Vec<u8> {
+    let mut s: String = TEXT.chars().cycle().take(len).collect();
+    while s.len() > len {
+        s.pop();
+    }
+    s.into_bytes()
+}
+
+fn validate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("utf8");
+
+    for &len in &[16, 32, 64, 128, 256, 4096, 65536] {
+        let bytes = input(len);
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("core", len), &bytes, |b, bytes| {
+            b.iter(|| str::from_utf8(black_box(bytes)).is_ok())
+        });
+        group.bench_with_input(BenchmarkId::new("simd", len), &bytes, |b, bytes| {
+            b.iter(|| simdutf8::basic::from_utf8(black_box(bytes)).is_ok())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, validate);
+criterion_main!(benches);
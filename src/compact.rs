@@ -0,0 +1,1113 @@
+use core::{
+    alloc::Layout,
+    borrow::{Borrow, BorrowMut},
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    mem::{self, ManuallyDrop, MaybeUninit},
+    ops, ptr, slice, str,
+};
+
+use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
+
+#[cfg(feature = "ffi")]
+use std::ffi::{OsStr, OsString};
+
+#[cfg(feature = "serde")]
+use serde::{
+    de::{Deserialize, Deserializer, Error, Visitor},
+    ser::{Serialize, Serializer},
+};
+
+use crate::{string::DrainTarget, Drain, SmallInteger, SmallString, TryReserveError};
+
+const WORD: usize = mem::size_of::<usize>();
+
+/// 内联存储的最大字节数：三个字减去存放标记的最后一个字节。
+const MAX_INLINE: usize = 3 * WORD - 1;
+
+/// 堆上容量只占用最后一个字中除标记外的字节。
+const MAX_CAPACITY: usize = usize::MAX >> 8;
+
+/// 存放在最后一个字节中的标记。
+///
+/// 内联时为`0xC0 + len`，溢出到堆上时为`0xFE`。其余取值都是无效的，
+/// 编译器可以用它们作为`Option<CompactSmallString>`的niche。
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum Tag {
+    Inline0 = 0xC0,
+    Inline1,
+    Inline2,
+    Inline3,
+    Inline4,
+    Inline5,
+    Inline6,
+    Inline7,
+    Inline8,
+    Inline9,
+    Inline10,
+    Inline11,
+    Inline12,
+    Inline13,
+    Inline14,
+    Inline15,
+    Inline16,
+    Inline17,
+    Inline18,
+    Inline19,
+    Inline20,
+    Inline21,
+    Inline22,
+    Inline23,
+    Heap = 0xFE,
+}
+
+impl Tag {
+    #[inline(always)]
+    fn inline(len: usize) -> Tag {
+        debug_assert!(len <= MAX_INLINE);
+        unsafe { mem::transmute::<u8, Tag>(Tag::Inline0 as u8 + len as u8) }
+    }
+}
+
+macro_rules! push_formatted {
+    ($($(#[$attr:meta])* fn $name:ident $(<$t:ident>)?($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            $(#[$attr])*
+            #[inline]
+            pub fn $name$(<$t: SmallInteger>)?(&mut self, $($arg: $ty),*) {
+                // 先在栈上格式化，再一次性追加
+                let mut buf = SmallString::<64>::new();
+                buf.$name($($arg),*);
+                self.push_str(&buf);
+            }
+        )*
+    };
+}
+
+/// 与`String`大小相同的小字符串。
+///
+/// `CompactSmallString`占用`size_of::<String>()`个字节（64位平台上为24字节），
+/// 最多可以内联存储`3 * size_of::<usize>() - 1`个字节（64位平台上为23字节），
+/// 长度和内联/堆标记存放在最后一个字节中。更长的字符串溢出到堆上，此时存放指针、长度和容量，
+/// 容量占用最后一个字中除标记外的字节。
+///
+/// 它提供构造、容量管理、追加、插入、删除、`drain`、`retain`以及格式化追加等与 [`SmallString`]
+/// 同名的方法，移动和嵌入其他结构体的开销与`String`相同。`replace_range`、`split_off`、
+/// `trim_*`/`strip_*`、`replace*`和大小写转换等方法没有提供，需要时可以对`as_str()`
+/// 或`as_mut_str()`操作，或者先转换为`SmallString`。
+/// 由于标记字节存在无效取值，`Option<CompactSmallString>`与`CompactSmallString`大小相同。
+///
+/// 堆上容量不能超过 [`MAX_CAPACITY`](#associatedconstant.MAX_CAPACITY)，
+/// 即`usize::MAX >> 8`字节，在32位平台上约为16 MiB。超过该限制时，
+/// 会增加容量的方法 panic，对应的`try_*`方法返回 [`TryReserveError::CapacityOverflow`]。
+///
+/// # Examples
+///
+/// ```
+/// use core::mem::size_of;
+/// use smallstr::CompactSmallString;
+///
+/// assert_eq!(size_of::<CompactSmallString>(), size_of::<String>());
+/// assert_eq!(size_of::<Option<CompactSmallString>>(), size_of::<String>());
+///
+/// let mut s = CompactSmallString::from("hello");
+/// assert!(!s.spilled());
+///
+/// s.push_str(", a string too long to be inline");
+/// assert!(s.spilled());
+/// assert_eq!(s, "hello, a string too long to be inline");
+/// ```
+#[repr(C)]
+pub struct CompactSmallString {
+    _align: [usize; 0],
+    bytes: [MaybeUninit<u8>; MAX_INLINE],
+    tag: Tag,
+}
+
+impl CompactSmallString {
+    /// 堆上容量的上限（字节）。
+    ///
+    /// 容量与标记共用最后一个字，因此只有`size_of::<usize>() - 1`个字节可用，
+    /// 在64位平台上为`2^56 - 1`，在32位平台上为`2^24 - 1`。
+    pub const MAX_CAPACITY: usize = MAX_CAPACITY;
+
+    /// 构造一个空字符串。
+    #[inline(always)]
+    pub const fn new() -> CompactSmallString {
+        CompactSmallString {
+            _align: [],
+            bytes: [MaybeUninit::uninit(); MAX_INLINE],
+            tag: Tag::Inline0,
+        }
+    }
+
+    /// 构造一个空字符串，并预先分配足够的容量来存储至少`N`个字节。
+    ///
+    /// 仅当`N`大于内联容量时才会创建堆分配。
+    #[inline(always)]
+    pub fn with_capacity(n: usize) -> CompactSmallString {
+        let mut string = CompactSmallString::new();
+        if n > MAX_INLINE {
+            string.grow(n);
+        }
+        string
+    }
+
+    /// 通过从`&str`复制数据来构造`CompactSmallString`。
+    #[inline(always)]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> CompactSmallString {
+        let mut string = CompactSmallString::with_capacity(s.len());
+        string.push_str(s);
+        string
+    }
+
+    /// 通过从`&str`复制数据来构造`CompactSmallString`。
+    ///
+    /// 如果分配内存失败，则返回错误而不是中止程序。
+    #[inline(always)]
+    pub fn try_from_str(s: &str) -> Result<CompactSmallString, TryReserveError> {
+        let mut string = CompactSmallString::new();
+        string.try_push_str(s)?;
+        Ok(string)
+    }
+
+    /// 使用现有分配构造`CompactSmallString`。
+    ///
+    /// 如果`s`足够短可以内联存储，则复制数据并释放原有的分配。
+    #[inline(always)]
+    pub fn from_string(s: String) -> CompactSmallString {
+        if s.len() <= MAX_INLINE || s.capacity() > MAX_CAPACITY {
+            return CompactSmallString::from_str(&s);
+        }
+
+        let mut string = CompactSmallString::new();
+        unsafe { string.set_heap(s.into_bytes()) };
+        string
+    }
+
+    /// 该字符串可以内联容纳的最大字节数。
+    #[inline(always)]
+    pub fn inline_size(&self) -> usize {
+        MAX_INLINE
+    }
+
+    /// 返回该字符串的长度（以字节为单位）。
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        if self.spilled() {
+            unsafe { *(self.bytes.as_ptr().add(WORD) as *const usize) }
+        } else {
+            (self.tag as u8 - Tag::Inline0 as u8) as usize
+        }
+    }
+
+    /// 如果此字符串为空，则返回`true`。
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 返回该字符串无需重新分配即可容纳的字节数。
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        if self.spilled() {
+            let mut buf = [0; WORD];
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    self.bytes.as_ptr().add(2 * WORD) as *const u8,
+                    buf.as_mut_ptr(),
+                    WORD - 1,
+                );
+            }
+            usize::from_le_bytes(buf)
+        } else {
+            MAX_INLINE
+        }
+    }
+
+    /// 如果数据已溢出到单独的堆分配缓冲区中，则返回`true`。
+    #[inline(always)]
+    pub fn spilled(&self) -> bool {
+        self.tag == Tag::Heap
+    }
+
+    /// 从字符串中移除指定的字节范围，并以迭代器的形式返回被移除的字符。
+    ///
+    /// 参见 [`SmallString::drain`]。
+    pub fn drain<R: ops::RangeBounds<usize>>(&mut self, range: R) -> Drain<'_> {
+        Drain::new(self, range)
+    }
+
+    /// 将给定的`char`附加到该字符串的末尾。
+    #[inline(always)]
+    pub fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]));
+    }
+
+    push_formatted! {
+        /// 将给定的整数附加到该字符串的末尾，参见 [`SmallString::push_integer`]。
+        fn push_integer<T>(num: T);
+        /// 将给定的整数以小写十六进制附加到该字符串的末尾，参见 [`SmallString::push_hex`]。
+        fn push_hex<T>(num: T);
+        /// 将给定的整数以大写十六进制附加到该字符串的末尾，参见 [`SmallString::push_hex_upper`]。
+        fn push_hex_upper<T>(num: T);
+        /// 将给定的整数以八进制附加到该字符串的末尾，参见 [`SmallString::push_octal`]。
+        fn push_octal<T>(num: T);
+        /// 将给定的整数以二进制附加到该字符串的末尾，参见 [`SmallString::push_binary`]。
+        fn push_binary<T>(num: T);
+        /// 将给定的整数以十进制附加到该字符串的末尾，并填充到至少`width`个字符，
+        /// 参见 [`SmallString::push_integer_padded`]。
+        fn push_integer_padded<T>(num: T, width: usize, fill: char);
+        /// 将给定的整数以小写十六进制附加到该字符串的末尾，并填充到至少`width`个字符。
+        fn push_hex_padded<T>(num: T, width: usize, fill: char);
+        /// 将给定的整数以大写十六进制附加到该字符串的末尾，并填充到至少`width`个字符。
+        fn push_hex_upper_padded<T>(num: T, width: usize, fill: char);
+        /// 将给定的整数以八进制附加到该字符串的末尾，并填充到至少`width`个字符。
+        fn push_octal_padded<T>(num: T, width: usize, fill: char);
+        /// 将给定的整数以二进制附加到该字符串的末尾，并填充到至少`width`个字符。
+        fn push_binary_padded<T>(num: T, width: usize, fill: char);
+        /// 将给定的`f64`以最短往返表示附加到该字符串的末尾，参见 [`SmallString::push_f64`]。
+        fn push_f64(num: f64);
+        /// 将给定的`f32`以最短往返表示附加到该字符串的末尾，参见 [`SmallString::push_f32`]。
+        fn push_f32(num: f32);
+        /// 将给定的`f64`保留`precision`位小数附加到该字符串的末尾，
+        /// 参见 [`SmallString::push_f64_fixed`]。
+        fn push_f64_fixed(num: f64, precision: usize);
+        /// 将给定的`f32`保留`precision`位小数附加到该字符串的末尾，
+        /// 参见 [`SmallString::push_f32_fixed`]。
+        fn push_f32_fixed(num: f32, precision: usize);
+    }
+
+    /// 将给定的字符串切片附加到该字符串的末尾。
+    #[inline(always)]
+    pub fn push_str(&mut self, s: &str) {
+        let len = self.len();
+        self.reserve(s.len());
+
+        unsafe {
+            ptr::copy_nonoverlapping(s.as_ptr(), self.data_mut_ptr().add(len), s.len());
+            self.set_len(len + s.len());
+        }
+    }
+
+    /// 从此字符串中删除最后一个字符并返回它。
+    ///
+    /// 如果字符串为空，则返回`None`。
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<char> {
+        match self.chars().next_back() {
+            Some(ch) => unsafe {
+                let new_len = self.len() - ch.len_utf8();
+                self.set_len(new_len);
+                Some(ch)
+            },
+            None => None,
+        }
+    }
+
+    /// 重新分配以将新容量设置为`new_cap`。
+    ///
+    /// 如果`new_cap`不超过内联容量，数据会移回内联存储。
+    ///
+    /// # Panics
+    ///
+    /// 如果`new_cap`小于当前长度。
+    #[inline(always)]
+    pub fn grow(&mut self, new_cap: usize) {
+        infallible(self.try_grow(new_cap));
+    }
+
+    /// 重新分配以将新容量设置为`new_cap`。
+    ///
+    /// 如果分配内存失败，则返回错误，字符串保持不变。
+    ///
+    /// # Panics
+    ///
+    /// 如果`new_cap`小于当前长度。
+    pub fn try_grow(&mut self, new_cap: usize) -> Result<(), TryReserveError> {
+        let len = self.len();
+        assert!(new_cap >= len);
+
+        unsafe {
+            if new_cap <= MAX_INLINE {
+                if self.spilled() {
+                    let vec = ManuallyDrop::into_inner(self.heap());
+                    ptr::copy_nonoverlapping(vec.as_ptr(), self.bytes.as_mut_ptr() as *mut u8, len);
+                    self.tag = Tag::inline(len);
+                }
+            } else if new_cap > MAX_CAPACITY {
+                return Err(TryReserveError::CapacityOverflow);
+            } else if self.spilled() {
+                let mut vec = self.heap();
+                if new_cap > vec.capacity() {
+                    vec.try_reserve_exact(new_cap - len)
+                        .map_err(|_| alloc_error(new_cap))?;
+                } else {
+                    vec.shrink_to(new_cap);
+                }
+                self.set_heap(ManuallyDrop::into_inner(vec));
+            } else {
+                let mut vec = Vec::new();
+                vec.try_reserve_exact(new_cap)
+                    .map_err(|_| alloc_error(new_cap))?;
+                vec.extend_from_slice(self.as_bytes());
+                self.set_heap(vec);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 确保该字符串的容量至少比其长度大`additional`字节。
+    ///
+    /// 为了防止频繁的重新分配，容量可以增加超过`additional`字节。
+    #[inline(always)]
+    pub fn reserve(&mut self, additional: usize) {
+        infallible(self.try_reserve(additional));
+    }
+
+    /// 确保该字符串的容量比其长度大`additional`字节。
+    ///
+    /// 与 [`reserve`](#method.reserve) 不同，该方法不会为了减少重新分配而额外分配容量。
+    #[inline(always)]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        infallible(self.try_reserve_exact(additional));
+    }
+
+    /// 确保该字符串的容量至少比其长度大`additional`字节。
+    ///
+    /// 如果容量溢出或分配内存失败，则返回错误，字符串保持不变。
+    #[inline(always)]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let len = self.len();
+        if self.capacity() - len >= additional {
+            return Ok(());
+        }
+
+        let new_cap = len
+            .checked_add(additional)
+            .and_then(usize::checked_next_power_of_two)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        self.try_grow(new_cap)
+    }
+
+    /// 确保该字符串的容量比其长度大`additional`字节。
+    ///
+    /// 如果容量溢出或分配内存失败，则返回错误，字符串保持不变。
+    #[inline(always)]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let len = self.len();
+        if self.capacity() - len >= additional {
+            return Ok(());
+        }
+
+        let new_cap = len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        self.try_grow(new_cap)
+    }
+
+    /// 尽可能缩小字符串的容量。
+    ///
+    /// 如果可能，这会将数据从外部堆缓冲区移动到字符串的内联存储。
+    #[inline(always)]
+    pub fn shrink_to_fit(&mut self) {
+        if self.spilled() {
+            self.grow(self.len());
+        }
+    }
+
+    /// 将字符串的容量缩小到不小于`min_capacity`和当前长度中的较大者。
+    ///
+    /// 如果该值不超过 [`inline_size`](#method.inline_size)，数据会从堆缓冲区移回内联存储。
+    /// 如果当前容量已经小于`min_capacity`，则不做任何事。
+    #[inline(always)]
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        if self.spilled() {
+            let new_cap = self.len().max(min_capacity);
+            if new_cap < self.capacity() {
+                self.grow(new_cap);
+            }
+        }
+    }
+
+    /// 缩短字符串，保留前一个`len`字节。
+    ///
+    /// 这不会重新分配。如果要缩小字符串的容量，请在截断后使用`shrink_to_fit`。
+    /// # Panics
+    ///
+    /// 如果`len`不在`char`边界上。
+    #[inline(always)]
+    pub fn truncate(&mut self, len: usize) {
+        assert!(self.is_char_boundary(len));
+        if len < self.len() {
+            unsafe { self.set_len(len) };
+        }
+    }
+
+    /// 提取包含整个字符串的字符串切片。
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(slice::from_raw_parts(self.data_ptr(), self.len())) }
+    }
+
+    /// 提取包含整个字符串的字符串切片。
+    #[inline(always)]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe {
+            let len = self.len();
+            str::from_utf8_unchecked_mut(slice::from_raw_parts_mut(self.data_mut_ptr(), len))
+        }
+    }
+
+    /// 删除字符串的所有内容。
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        unsafe { self.set_len(0) };
+    }
+
+    /// 从此字符串中的字节位置删除`char`并返回它。
+    /// # Panics
+    ///
+    /// 如果`index`不在`char`边界上。
+    #[inline(always)]
+    pub fn remove(&mut self, index: usize) -> char {
+        let ch = match self[index..].chars().next() {
+            Some(ch) => ch,
+            None => panic!("cannot remove a char from the end of a string"),
+        };
+
+        let ch_len = ch.len_utf8();
+        let next = index + ch_len;
+        let len = self.len();
+
+        unsafe {
+            let ptr = self.data_mut_ptr();
+            ptr::copy(ptr.add(next), ptr.add(index), len - next);
+            self.set_len(len - ch_len);
+        }
+
+        ch
+    }
+
+    /// 将`char`插入到该字符串的给定字节位置。
+    /// # Panics
+    ///
+    /// 如果`index`不在`char`边界上。
+    #[inline(always)]
+    pub fn insert(&mut self, index: usize, ch: char) {
+        self.insert_str(index, ch.encode_utf8(&mut [0; 4]));
+    }
+
+    /// 将 `&str` 插入到该字符串的给定字节位置。
+    /// # Panics
+    ///
+    /// 如果`index`不在`char`边界上。
+    #[inline(always)]
+    pub fn insert_str(&mut self, index: usize, s: &str) {
+        assert!(self.is_char_boundary(index));
+
+        let len = self.len();
+        let amt = s.len();
+
+        self.reserve(amt);
+
+        unsafe {
+            let ptr = self.data_mut_ptr();
+            ptr::copy(ptr.add(index), ptr.add(index + amt), len - index);
+            ptr::copy_nonoverlapping(s.as_ptr(), ptr.add(index), amt);
+            self.set_len(len + amt);
+        }
+    }
+
+    /// 将给定的`char`附加到该字符串的末尾。
+    ///
+    /// 如果分配内存失败，则返回错误，字符串保持不变。
+    #[inline(always)]
+    pub fn try_push(&mut self, ch: char) -> Result<(), TryReserveError> {
+        self.try_push_str(ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// 将给定的字符串切片附加到该字符串的末尾。
+    ///
+    /// 如果分配内存失败，则返回错误，字符串保持不变。
+    #[inline(always)]
+    pub fn try_push_str(&mut self, s: &str) -> Result<(), TryReserveError> {
+        self.try_reserve(s.len())?;
+        self.push_str(s);
+        Ok(())
+    }
+
+    /// 将`char`插入到该字符串的给定字节位置。
+    ///
+    /// 如果分配内存失败，则返回错误，字符串保持不变。
+    /// # Panics
+    ///
+    /// 如果`index`不在`char`边界上。
+    #[inline(always)]
+    pub fn try_insert(&mut self, index: usize, ch: char) -> Result<(), TryReserveError> {
+        self.try_insert_str(index, ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// 将 `&str` 插入到该字符串的给定字节位置。
+    ///
+    /// 如果分配内存失败，则返回错误，字符串保持不变。
+    /// # Panics
+    ///
+    /// 如果`index`不在`char`边界上。
+    #[inline(always)]
+    pub fn try_insert_str(&mut self, index: usize, s: &str) -> Result<(), TryReserveError> {
+        assert!(self.is_char_boundary(index));
+
+        self.try_reserve(s.len())?;
+        self.insert_str(index, s);
+        Ok(())
+    }
+
+    /// 将给定的整数以十进制附加到该字符串的末尾。
+    ///
+    /// 如果分配内存失败，则返回错误，字符串保持不变。
+    #[inline(always)]
+    pub fn try_push_integer<T: SmallInteger>(&mut self, num: T) -> Result<(), TryReserveError> {
+        let mut buf = SmallString::<40>::new();
+        buf.push_integer(num);
+        self.try_push_str(&buf)
+    }
+
+    /// 如果`CompactSmallString`已经溢出到堆上，则直接转移堆缓冲区而不重新分配内存。
+    /// 否则会创建`String`并将内联数据拷贝过去。
+    #[inline(always)]
+    pub fn into_string(self) -> String {
+        let this = ManuallyDrop::new(self);
+        if this.spilled() {
+            unsafe { String::from_utf8_unchecked(ManuallyDrop::into_inner(this.heap())) }
+        } else {
+            String::from(this.as_str())
+        }
+    }
+
+    /// 将 `CompactSmallString`转换为`Box<str>`，如果已经溢出到堆上，则不进行分配。
+    ///
+    /// 请注意，这将减少过剩产能。
+    #[inline(always)]
+    pub fn into_boxed_str(self) -> Box<str> {
+        self.into_string().into_boxed_str()
+    }
+
+    /// 仅保留谓词指定的字符。
+    ///
+    /// 换句话说，删除所有字符`c`，以便`f(c)`返回`false`。此方法就地运行并保留保留字符的顺序。
+    pub fn retain<F: FnMut(char) -> bool>(&mut self, mut f: F) {
+        struct SetLenOnDrop<'a> {
+            s: &'a mut CompactSmallString,
+            idx: usize,
+            del_bytes: usize,
+        }
+
+        impl Drop for SetLenOnDrop<'_> {
+            fn drop(&mut self) {
+                let new_len = self.idx - self.del_bytes;
+                debug_assert!(new_len <= self.s.len());
+                unsafe { self.s.set_len(new_len) };
+            }
+        }
+
+        let len = self.len();
+        let mut guard = SetLenOnDrop {
+            s: self,
+            idx: 0,
+            del_bytes: 0,
+        };
+
+        while guard.idx < len {
+            let ch = unsafe {
+                guard
+                    .s
+                    .get_unchecked(guard.idx..len)
+                    .chars()
+                    .next()
+                    .unwrap()
+            };
+            let ch_len = ch.len_utf8();
+
+            if !f(ch) {
+                guard.del_bytes += ch_len;
+            } else if guard.del_bytes > 0 {
+                unsafe {
+                    let ptr = guard.s.data_mut_ptr();
+                    ptr::copy(
+                        ptr.add(guard.idx),
+                        ptr.add(guard.idx - guard.del_bytes),
+                        ch_len,
+                    );
+                }
+            }
+
+            // Point idx to the next char
+            guard.idx += ch_len;
+        }
+
+        drop(guard);
+    }
+
+    #[inline(always)]
+    fn data_ptr(&self) -> *const u8 {
+        if self.spilled() {
+            unsafe { *(self.bytes.as_ptr() as *const *const u8) }
+        } else {
+            self.bytes.as_ptr() as *const u8
+        }
+    }
+
+    #[inline(always)]
+    fn data_mut_ptr(&mut self) -> *mut u8 {
+        if self.spilled() {
+            unsafe { *(self.bytes.as_ptr() as *const *mut u8) }
+        } else {
+            self.bytes.as_mut_ptr() as *mut u8
+        }
+    }
+
+    /// 设置字符串的长度，`len`不能超过当前容量。
+    #[inline(always)]
+    unsafe fn set_len(&mut self, len: usize) {
+        debug_assert!(len <= self.capacity());
+        if self.spilled() {
+            *(self.bytes.as_mut_ptr().add(WORD) as *mut usize) = len;
+        } else {
+            self.tag = Tag::inline(len);
+        }
+    }
+
+    /// 以`Vec`的形式借出堆缓冲区，调用者负责写回或释放它。
+    #[inline(always)]
+    unsafe fn heap(&self) -> ManuallyDrop<Vec<u8>> {
+        debug_assert!(self.spilled());
+        let ptr = *(self.bytes.as_ptr() as *const *mut u8);
+        ManuallyDrop::new(Vec::from_raw_parts(ptr, self.len(), self.capacity()))
+    }
+
+    /// 接管`vec`的缓冲区，不释放当前的堆缓冲区。
+    #[inline(always)]
+    unsafe fn set_heap(&mut self, vec: Vec<u8>) {
+        let mut vec = ManuallyDrop::new(vec);
+        debug_assert!(vec.capacity() <= MAX_CAPACITY);
+
+        let base = self.bytes.as_mut_ptr() as *mut u8;
+        *(base as *mut *mut u8) = vec.as_mut_ptr();
+        *(base.add(WORD) as *mut usize) = vec.len();
+        ptr::copy_nonoverlapping(
+            vec.capacity().to_le_bytes().as_ptr(),
+            base.add(2 * WORD),
+            WORD - 1,
+        );
+        self.tag = Tag::Heap;
+    }
+}
+
+#[inline(always)]
+fn alloc_error(capacity: usize) -> TryReserveError {
+    match Layout::array::<u8>(capacity) {
+        Ok(layout) => TryReserveError::AllocError { layout },
+        Err(_) => TryReserveError::CapacityOverflow,
+    }
+}
+
+#[inline(always)]
+fn infallible<T>(result: Result<T, TryReserveError>) -> T {
+    match result {
+        Ok(x) => x,
+        Err(TryReserveError::CapacityOverflow) => panic!("capacity overflow"),
+        Err(TryReserveError::AllocError { layout }) => alloc::alloc::handle_alloc_error(layout),
+    }
+}
+
+impl DrainTarget for CompactSmallString {
+    #[inline(always)]
+    fn as_mut_str(&mut self) -> &mut str {
+        CompactSmallString::as_mut_str(self)
+    }
+
+    #[inline(always)]
+    unsafe fn set_len(&mut self, len: usize) {
+        CompactSmallString::set_len(self, len);
+    }
+}
+
+impl Drop for CompactSmallString {
+    #[inline(always)]
+    fn drop(&mut self) {
+        if self.spilled() {
+            unsafe { drop(ManuallyDrop::into_inner(self.heap())) };
+        }
+    }
+}
+
+impl Clone for CompactSmallString {
+    #[inline(always)]
+    fn clone(&self) -> CompactSmallString {
+        CompactSmallString::from_str(self)
+    }
+}
+
+impl Default for CompactSmallString {
+    #[inline(always)]
+    fn default() -> CompactSmallString {
+        CompactSmallString::new()
+    }
+}
+
+impl ops::Deref for CompactSmallString {
+    type Target = str;
+
+    #[inline(always)]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl ops::DerefMut for CompactSmallString {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl AsRef<str> for CompactSmallString {
+    #[inline(always)]
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl AsMut<str> for CompactSmallString {
+    #[inline(always)]
+    fn as_mut(&mut self) -> &mut str {
+        self
+    }
+}
+
+impl Borrow<str> for CompactSmallString {
+    #[inline(always)]
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl BorrowMut<str> for CompactSmallString {
+    #[inline(always)]
+    fn borrow_mut(&mut self) -> &mut str {
+        self
+    }
+}
+
+impl AsRef<[u8]> for CompactSmallString {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl fmt::Write for CompactSmallString {
+    #[inline(always)]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        self.push(ch);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for CompactSmallString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for CompactSmallString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(CompactSmallStringVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct CompactSmallStringVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for CompactSmallStringVisitor {
+    type Value = CompactSmallString;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+}
+
+impl From<char> for CompactSmallString {
+    #[inline(always)]
+    fn from(ch: char) -> CompactSmallString {
+        CompactSmallString::from_str(ch.encode_utf8(&mut [0; 4]))
+    }
+}
+
+impl From<&str> for CompactSmallString {
+    #[inline(always)]
+    fn from(s: &str) -> CompactSmallString {
+        CompactSmallString::from_str(s)
+    }
+}
+
+impl From<Box<str>> for CompactSmallString {
+    #[inline(always)]
+    fn from(s: Box<str>) -> CompactSmallString {
+        CompactSmallString::from_string(s.into())
+    }
+}
+
+impl From<String> for CompactSmallString {
+    #[inline(always)]
+    fn from(s: String) -> CompactSmallString {
+        CompactSmallString::from_string(s)
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for CompactSmallString {
+    fn from(value: Cow<'a, str>) -> Self {
+        match value {
+            Cow::Borrowed(s) => Self::from_str(s),
+            Cow::Owned(s) => Self::from_string(s),
+        }
+    }
+}
+
+impl From<CompactSmallString> for String {
+    #[inline(always)]
+    fn from(s: CompactSmallString) -> String {
+        s.into_string()
+    }
+}
+
+impl FromIterator<char> for CompactSmallString {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> CompactSmallString {
+        let mut s = CompactSmallString::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl<'a> FromIterator<&'a char> for CompactSmallString {
+    fn from_iter<I: IntoIterator<Item = &'a char>>(iter: I) -> CompactSmallString {
+        let mut s = CompactSmallString::new();
+        s.extend(iter.into_iter().cloned());
+        s
+    }
+}
+
+impl<'a> FromIterator<Cow<'a, str>> for CompactSmallString {
+    fn from_iter<I: IntoIterator<Item = Cow<'a, str>>>(iter: I) -> CompactSmallString {
+        let mut s = CompactSmallString::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl<'a> FromIterator<&'a str> for CompactSmallString {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> CompactSmallString {
+        let mut s = CompactSmallString::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl FromIterator<String> for CompactSmallString {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> CompactSmallString {
+        let mut s = CompactSmallString::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl Extend<char> for CompactSmallString {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        let (lo, _) = iter.size_hint();
+
+        self.reserve(lo);
+
+        for ch in iter {
+            self.push(ch);
+        }
+    }
+}
+
+impl<'a> Extend<&'a char> for CompactSmallString {
+    fn extend<I: IntoIterator<Item = &'a char>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl<'a> Extend<Cow<'a, str>> for CompactSmallString {
+    fn extend<I: IntoIterator<Item = Cow<'a, str>>>(&mut self, iter: I) {
+        for s in iter {
+            self.push_str(&s);
+        }
+    }
+}
+
+impl<'a> Extend<&'a str> for CompactSmallString {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        for s in iter {
+            self.push_str(s);
+        }
+    }
+}
+
+impl Extend<String> for CompactSmallString {
+    fn extend<I: IntoIterator<Item = String>>(&mut self, iter: I) {
+        for s in iter {
+            self.push_str(&s);
+        }
+    }
+}
+
+impl fmt::Debug for CompactSmallString {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl fmt::Display for CompactSmallString {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+macro_rules! eq_str {
+    ( $rhs:ty ) => {
+        impl<'a> PartialEq<$rhs> for CompactSmallString {
+            #[inline(always)]
+            fn eq(&self, rhs: &$rhs) -> bool {
+                self[..] == rhs[..]
+            }
+        }
+
+        impl<'a> PartialEq<CompactSmallString> for $rhs {
+            #[inline(always)]
+            fn eq(&self, rhs: &CompactSmallString) -> bool {
+                self[..] == rhs[..]
+            }
+        }
+
+        impl<'a> PartialOrd<$rhs> for CompactSmallString {
+            #[inline(always)]
+            fn partial_cmp(&self, rhs: &$rhs) -> Option<Ordering> {
+                Some(self[..].cmp(&rhs[..]))
+            }
+        }
+
+        impl<'a> PartialOrd<CompactSmallString> for $rhs {
+            #[inline(always)]
+            fn partial_cmp(&self, rhs: &CompactSmallString) -> Option<Ordering> {
+                Some(self[..].cmp(&rhs[..]))
+            }
+        }
+    };
+}
+
+eq_str!(str);
+eq_str!(&'a str);
+eq_str!(String);
+eq_str!(Cow<'a, str>);
+
+/// 与`OsStr`比较时，将字符串视为`OsStr`。
+#[cfg(feature = "ffi")]
+macro_rules! eq_os_str {
+    ( $rhs:ty ) => {
+        impl<'a> PartialEq<$rhs> for CompactSmallString {
+            #[inline(always)]
+            fn eq(&self, rhs: &$rhs) -> bool {
+                OsStr::new(&self[..]) == AsRef::<OsStr>::as_ref(rhs)
+            }
+        }
+
+        impl<'a> PartialEq<CompactSmallString> for $rhs {
+            #[inline(always)]
+            fn eq(&self, rhs: &CompactSmallString) -> bool {
+                AsRef::<OsStr>::as_ref(self) == OsStr::new(&rhs[..])
+            }
+        }
+
+        impl<'a> PartialOrd<$rhs> for CompactSmallString {
+            #[inline(always)]
+            fn partial_cmp(&self, rhs: &$rhs) -> Option<Ordering> {
+                OsStr::new(&self[..]).partial_cmp(AsRef::<OsStr>::as_ref(rhs))
+            }
+        }
+
+        impl<'a> PartialOrd<CompactSmallString> for $rhs {
+            #[inline(always)]
+            fn partial_cmp(&self, rhs: &CompactSmallString) -> Option<Ordering> {
+                AsRef::<OsStr>::as_ref(self).partial_cmp(OsStr::new(&rhs[..]))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "ffi")]
+eq_os_str!(OsStr);
+#[cfg(feature = "ffi")]
+eq_os_str!(&'a OsStr);
+#[cfg(feature = "ffi")]
+eq_os_str!(OsString);
+#[cfg(feature = "ffi")]
+eq_os_str!(Cow<'a, OsStr>);
+
+impl PartialEq for CompactSmallString {
+    #[inline(always)]
+    fn eq(&self, rhs: &CompactSmallString) -> bool {
+        self[..] == rhs[..]
+    }
+}
+
+impl Eq for CompactSmallString {}
+
+impl PartialOrd for CompactSmallString {
+    #[inline(always)]
+    fn partial_cmp(&self, rhs: &CompactSmallString) -> Option<Ordering> {
+        Some(self.cmp(rhs))
+    }
+}
+
+impl Ord for CompactSmallString {
+    #[inline(always)]
+    fn cmp(&self, rhs: &CompactSmallString) -> Ordering {
+        self[..].cmp(&rhs[..])
+    }
+}
+
+impl Hash for CompactSmallString {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self[..].hash(state)
+    }
+}
@@ -0,0 +1,298 @@
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    convert::TryFrom,
+    ffi::{c_char, CStr},
+    fmt,
+    hash::{Hash, Hasher},
+    ops,
+    str::Utf8Error,
+};
+
+use alloc::ffi::CString;
+
+use smallvec::SmallVec;
+
+use crate::{utf8, FromUtf8Error, NulError, SmallString};
+
+/// 以 NUL 结尾、可以内联存储少量字节的 C 字符串，与 [`SmallString`] 配合用于 FFI。
+///
+/// 末尾的 NUL 与内容一起存放在同一个`SmallVec<u8, SIZE>`中，因此`SIZE`包括这个 NUL：
+/// 不超过`SIZE - 1`个字节的内容可以内联存储，传给 C 函数时不需要像`CString`那样分配内存。
+///
+/// [`SmallString`]: struct.SmallString.html
+#[derive(Clone)]
+pub struct SmallCString<const SIZE: usize = { const { 1 << 12 } }> {
+    // 总是以唯一的 NUL 结尾
+    data: SmallVec<u8, SIZE>,
+}
+
+impl<const SIZE: usize> SmallCString<SIZE> {
+    /// 构造一个空的 C 字符串。
+    #[inline(always)]
+    pub fn new() -> SmallCString<SIZE> {
+        let mut data = SmallVec::new();
+        data.push(0);
+        SmallCString { data }
+    }
+
+    /// 通过复制字节构造`SmallCString`，并在末尾添加 NUL。
+    ///
+    /// 如果`bytes`中包含 NUL，则返回错误。
+    #[inline(always)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<SmallCString<SIZE>, NulError<SIZE>> {
+        let mut data = SmallVec::with_capacity(bytes.len() + 1);
+        data.extend_from_slice(bytes);
+        SmallCString::from_vec(data)
+    }
+
+    /// 使用现有的字节缓冲区构造`SmallCString`，并在末尾添加 NUL。
+    ///
+    /// 如果`vec`中包含 NUL，返回的错误中包含原始缓冲区。
+    /// 如果`vec`正好填满内联缓冲区，添加 NUL 会使其溢出到堆中。
+    #[inline(always)]
+    pub fn from_vec(vec: SmallVec<u8, SIZE>) -> Result<SmallCString<SIZE>, NulError<SIZE>> {
+        match vec.iter().position(|&b| b == 0) {
+            Some(position) => Err(NulError {
+                position,
+                bytes: vec,
+            }),
+            None => Ok(unsafe { SmallCString::from_vec_unchecked(vec) }),
+        }
+    }
+
+    /// 使用现有的字节缓冲区构造`SmallCString`，而不检查其中是否包含 NUL。
+    ///
+    /// # Safety
+    ///
+    /// `vec`中不能包含 NUL，否则`as_c_str`等方法返回的字符串会在第一个 NUL 处截断，
+    /// 与 [`len`] 不一致。
+    ///
+    /// [`len`]: #method.len
+    #[inline(always)]
+    pub unsafe fn from_vec_unchecked(mut vec: SmallVec<u8, SIZE>) -> SmallCString<SIZE> {
+        vec.push(0);
+        SmallCString { data: vec }
+    }
+
+    /// 将`SmallString`转换为`SmallCString`，复用其缓冲区。
+    ///
+    /// 如果字符串中包含 NUL，则返回错误。
+    #[inline(always)]
+    pub fn from_small_string(s: SmallString<SIZE>) -> Result<SmallCString<SIZE>, NulError<SIZE>> {
+        SmallCString::from_vec(s.data)
+    }
+
+    /// 通过从`&CStr`复制数据来构造`SmallCString`。
+    #[inline(always)]
+    pub fn from_c_str(s: &CStr) -> SmallCString<SIZE> {
+        SmallCString {
+            data: SmallVec::from(s.to_bytes_with_nul()),
+        }
+    }
+
+    /// 提取包含整个字符串的`CStr`。
+    #[inline(always)]
+    pub fn as_c_str(&self) -> &CStr {
+        unsafe { CStr::from_bytes_with_nul_unchecked(&self.data) }
+    }
+
+    /// 返回指向以 NUL 结尾的内容的指针，可以直接传给 C 函数。
+    ///
+    /// 指针在`SmallCString`被修改、移动或丢弃之前有效：内容内联存储时，移动
+    /// `SmallCString`也会移动其内容。
+    #[inline(always)]
+    pub fn as_ptr(&self) -> *const c_char {
+        self.data.as_ptr() as *const c_char
+    }
+
+    /// 返回不含末尾 NUL 的字节切片。
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data[..self.len()]
+    }
+
+    /// 返回包含末尾 NUL 的字节切片。
+    #[inline(always)]
+    pub fn as_bytes_with_nul(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// 如果内容是有效的 UTF-8，则返回对应的字符串切片。
+    #[inline(always)]
+    pub fn to_str(&self) -> Result<&str, Utf8Error> {
+        utf8::from_utf8(self.as_bytes())
+    }
+
+    /// 返回不含末尾 NUL 的长度（以字节为单位）。
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.data.len() - 1
+    }
+
+    /// 如果字符串为空，则返回`true`。
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 可以内联存储的最大字节数，包括末尾的 NUL。
+    #[inline(always)]
+    pub fn inline_size(&self) -> usize {
+        SmallVec::<u8, SIZE>::inline_size()
+    }
+
+    /// 如果数据已溢出到单独的堆分配缓冲区中，则返回`true`。
+    #[inline(always)]
+    pub fn spilled(&self) -> bool {
+        self.data.spilled()
+    }
+
+    /// 返回不含末尾 NUL 的字节缓冲区。
+    #[inline(always)]
+    pub fn into_bytes(mut self) -> SmallVec<u8, SIZE> {
+        self.data.pop();
+        self.data
+    }
+
+    /// 将`SmallCString`转换为`SmallString`，复用其缓冲区。
+    ///
+    /// 如果内容不是有效的 UTF-8，返回的错误中包含不含 NUL 的字节缓冲区。
+    #[inline(always)]
+    pub fn into_small_string(self) -> Result<SmallString<SIZE>, FromUtf8Error<SIZE>> {
+        SmallString::from_utf8(self.into_bytes())
+    }
+
+    /// 将`SmallCString`转换为`CString`。
+    ///
+    /// 如果数据已溢出到堆中，则复用该分配。
+    #[inline(always)]
+    pub fn into_c_string(self) -> CString {
+        unsafe { CString::from_vec_with_nul_unchecked(self.data.into_vec()) }
+    }
+}
+
+impl<const SIZE: usize> Default for SmallCString<SIZE> {
+    #[inline(always)]
+    fn default() -> SmallCString<SIZE> {
+        SmallCString::new()
+    }
+}
+
+impl<const SIZE: usize> ops::Deref for SmallCString<SIZE> {
+    type Target = CStr;
+
+    #[inline(always)]
+    fn deref(&self) -> &CStr {
+        self.as_c_str()
+    }
+}
+
+impl<const SIZE: usize> AsRef<CStr> for SmallCString<SIZE> {
+    #[inline(always)]
+    fn as_ref(&self) -> &CStr {
+        self.as_c_str()
+    }
+}
+
+impl<const SIZE: usize> Borrow<CStr> for SmallCString<SIZE> {
+    #[inline(always)]
+    fn borrow(&self) -> &CStr {
+        self.as_c_str()
+    }
+}
+
+impl<const SIZE: usize> fmt::Debug for SmallCString<SIZE> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_c_str(), f)
+    }
+}
+
+impl<const SIZE: usize> From<&CStr> for SmallCString<SIZE> {
+    #[inline(always)]
+    fn from(s: &CStr) -> SmallCString<SIZE> {
+        SmallCString::from_c_str(s)
+    }
+}
+
+impl<const SIZE: usize> From<SmallCString<SIZE>> for CString {
+    #[inline(always)]
+    fn from(s: SmallCString<SIZE>) -> CString {
+        s.into_c_string()
+    }
+}
+
+impl<const SIZE: usize> TryFrom<&str> for SmallCString<SIZE> {
+    type Error = NulError<SIZE>;
+
+    #[inline(always)]
+    fn try_from(s: &str) -> Result<SmallCString<SIZE>, NulError<SIZE>> {
+        SmallCString::from_bytes(s.as_bytes())
+    }
+}
+
+impl<const SIZE: usize> TryFrom<SmallString<SIZE>> for SmallCString<SIZE> {
+    type Error = NulError<SIZE>;
+
+    #[inline(always)]
+    fn try_from(s: SmallString<SIZE>) -> Result<SmallCString<SIZE>, NulError<SIZE>> {
+        SmallCString::from_small_string(s)
+    }
+}
+
+impl<const SIZE: usize> TryFrom<SmallCString<SIZE>> for SmallString<SIZE> {
+    type Error = FromUtf8Error<SIZE>;
+
+    #[inline(always)]
+    fn try_from(s: SmallCString<SIZE>) -> Result<SmallString<SIZE>, FromUtf8Error<SIZE>> {
+        s.into_small_string()
+    }
+}
+
+/// 只在相同的`SIZE`之间实现，以免右侧的类型无法推断；
+/// 不同`SIZE`之间可以比较`as_c_str()`。
+impl<const SIZE: usize> PartialEq for SmallCString<SIZE> {
+    #[inline(always)]
+    fn eq(&self, other: &SmallCString<SIZE>) -> bool {
+        self.as_c_str() == other.as_c_str()
+    }
+}
+
+impl<const SIZE: usize> Eq for SmallCString<SIZE> {}
+
+impl<const SIZE: usize> PartialEq<CStr> for SmallCString<SIZE> {
+    #[inline(always)]
+    fn eq(&self, other: &CStr) -> bool {
+        self.as_c_str() == other
+    }
+}
+
+impl<'a, const SIZE: usize> PartialEq<&'a CStr> for SmallCString<SIZE> {
+    #[inline(always)]
+    fn eq(&self, other: &&'a CStr) -> bool {
+        self.as_c_str() == *other
+    }
+}
+
+impl<const SIZE: usize> PartialOrd for SmallCString<SIZE> {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &SmallCString<SIZE>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const SIZE: usize> Ord for SmallCString<SIZE> {
+    #[inline(always)]
+    fn cmp(&self, other: &SmallCString<SIZE>) -> Ordering {
+        self.as_c_str().cmp(other.as_c_str())
+    }
+}
+
+/// 与`CStr`的哈希值相同，因此可以用`&CStr`查询以`SmallCString`为键的哈希表。
+impl<const SIZE: usize> Hash for SmallCString<SIZE> {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_c_str().hash(state)
+    }
+}
@@ -0,0 +1,157 @@
+use core::{alloc::Layout, fmt, str::Utf8Error};
+
+use smallvec::{CollectionAllocErr, SmallVec};
+
+/// 当操作需要超出字符串当前容量时由`try_*`方法返回的错误。
+///
+/// 出现该错误时字符串的内容保持不变。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapacityError;
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("insufficient capacity")
+    }
+}
+
+impl core::error::Error for CapacityError {}
+
+/// 从 UTF-16 构造`SmallString`时，如果输入无效则返回的错误。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FromUtf16Error {
+    /// 输入包含未配对的代理项。
+    LoneSurrogate,
+    /// 按字节解码时字节数为奇数。
+    OddLength,
+}
+
+impl fmt::Display for FromUtf16Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid utf-16: ")?;
+        match self {
+            FromUtf16Error::LoneSurrogate => f.write_str("lone surrogate found"),
+            FromUtf16Error::OddLength => f.write_str("odd number of bytes"),
+        }
+    }
+}
+
+impl core::error::Error for FromUtf16Error {}
+
+/// 当`try_*`方法分配内存失败时返回的错误。
+///
+/// 出现该错误时字符串的内容保持不变。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// 所需的容量超过了`usize::MAX`或`isize::MAX`字节。
+    CapacityOverflow,
+    /// 内存分配器返回了错误。
+    AllocError {
+        /// 传递给内存分配器的布局。
+        layout: Layout,
+    },
+}
+
+impl From<CollectionAllocErr> for TryReserveError {
+    #[inline(always)]
+    fn from(error: CollectionAllocErr) -> TryReserveError {
+        match error {
+            CollectionAllocErr::CapacityOverflow => TryReserveError::CapacityOverflow,
+            CollectionAllocErr::AllocErr { layout } => TryReserveError::AllocError { layout },
+        }
+    }
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("memory allocation failed")?;
+        match self {
+            TryReserveError::CapacityOverflow => {
+                f.write_str(" because the computed capacity exceeded the collection's maximum")
+            }
+            TryReserveError::AllocError { .. } => {
+                f.write_str(" because the memory allocator returned an error")
+            }
+        }
+    }
+}
+
+impl core::error::Error for TryReserveError {}
+
+/// 从字节缓冲区构造`SmallString`时，如果字节不是有效的 UTF-8 则返回的错误。
+///
+/// 与`alloc::string::FromUtf8Error`一致，原始缓冲区可以通过 [`into_bytes`] 取回，
+/// 不会丢失已有的分配。
+///
+/// [`into_bytes`]: #method.into_bytes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FromUtf8Error<const SIZE: usize = { const { 1 << 12 } }> {
+    pub(crate) bytes: SmallVec<u8, SIZE>,
+    pub(crate) error: Utf8Error,
+}
+
+impl<const SIZE: usize> FromUtf8Error<SIZE> {
+    /// 返回转换失败的字节。
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// 取回转换失败的字节缓冲区。
+    #[inline(always)]
+    pub fn into_bytes(self) -> SmallVec<u8, SIZE> {
+        self.bytes
+    }
+
+    /// 返回描述转换失败原因的`Utf8Error`。
+    #[inline(always)]
+    pub fn utf8_error(&self) -> Utf8Error {
+        self.error
+    }
+}
+
+impl<const SIZE: usize> fmt::Display for FromUtf8Error<SIZE> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl<const SIZE: usize> core::error::Error for FromUtf8Error<SIZE> {}
+
+/// 构造`SmallCString`时，如果输入的字节中包含 NUL 则返回的错误。
+///
+/// 与`alloc::ffi::NulError`一致，可以通过 [`nul_position`] 得到第一个 NUL 的位置，
+/// 并通过 [`into_bytes`] 取回原始字节。
+///
+/// [`nul_position`]: #method.nul_position
+/// [`into_bytes`]: #method.into_bytes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NulError<const SIZE: usize = { const { 1 << 12 } }> {
+    pub(crate) position: usize,
+    pub(crate) bytes: SmallVec<u8, SIZE>,
+}
+
+impl<const SIZE: usize> NulError<SIZE> {
+    /// 返回输入中第一个 NUL 字节的位置。
+    #[inline(always)]
+    pub fn nul_position(&self) -> usize {
+        self.position
+    }
+
+    /// 取回构造失败的字节缓冲区。
+    #[inline(always)]
+    pub fn into_bytes(self) -> SmallVec<u8, SIZE> {
+        self.bytes
+    }
+}
+
+impl<const SIZE: usize> fmt::Display for NulError<SIZE> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "nul byte found in provided data at position: {}",
+            self.position
+        )
+    }
+}
+
+impl<const SIZE: usize> core::error::Error for NulError<SIZE> {}
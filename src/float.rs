@@ -0,0 +1,482 @@
+//! 浮点数格式化：最短往返表示（Ryu 算法）和定点小数表示。
+//!
+//! 输出格式与`core::fmt`的`Display`一致：不使用科学计数法，`NaN`不带符号，
+//! 负零输出为`-0`。
+
+use crate::{integer, SmallString};
+
+mod table;
+
+use self::table::{DOUBLE_POW5_INV_SPLIT, DOUBLE_POW5_SPLIT};
+
+const POW5_INV_BITCOUNT: i32 = 125;
+const POW5_BITCOUNT: i32 = 125;
+
+/// 解码后的 IEEE 754 浮点数。
+pub(crate) enum Decoded {
+    /// 非数字。
+    Nan,
+    /// 无穷大。
+    Infinite,
+    /// 零。
+    Zero,
+    /// 有限的非零值，等于`mantissa * 2^exponent`。
+    Finite {
+        /// 原始的尾数字段（不含隐含的最高位）。
+        ieee_mantissa: u64,
+        /// 原始的指数字段。
+        ieee_exponent: u32,
+    },
+}
+
+/// 浮点数的位布局。
+pub(crate) struct Format {
+    mantissa_bits: u32,
+    bias: i32,
+}
+
+pub(crate) const F64: Format = Format {
+    mantissa_bits: 52,
+    bias: 1023,
+};
+
+pub(crate) const F32: Format = Format {
+    mantissa_bits: 23,
+    bias: 127,
+};
+
+impl Format {
+    /// 将浮点数的位模式拆分为符号和解码后的值。
+    #[inline(always)]
+    pub(crate) fn decode(&self, bits: u64, exponent_bits: u32) -> (bool, Decoded) {
+        let negative = (bits >> (self.mantissa_bits + exponent_bits)) & 1 != 0;
+        let ieee_mantissa = bits & ((1 << self.mantissa_bits) - 1);
+        let ieee_exponent = ((bits >> self.mantissa_bits) & ((1 << exponent_bits) - 1)) as u32;
+
+        let decoded = if ieee_exponent == (1 << exponent_bits) - 1 {
+            if ieee_mantissa == 0 {
+                Decoded::Infinite
+            } else {
+                Decoded::Nan
+            }
+        } else if ieee_exponent == 0 && ieee_mantissa == 0 {
+            Decoded::Zero
+        } else {
+            Decoded::Finite {
+                ieee_mantissa,
+                ieee_exponent,
+            }
+        };
+
+        (negative, decoded)
+    }
+
+    /// 返回`(m2, e2)`，使得浮点数的值恰好等于`m2 * 2^e2`。
+    #[inline(always)]
+    fn binary(&self, ieee_mantissa: u64, ieee_exponent: u32) -> (u64, i32) {
+        if ieee_exponent == 0 {
+            (ieee_mantissa, 1 - self.bias - self.mantissa_bits as i32)
+        } else {
+            (
+                (1 << self.mantissa_bits) | ieee_mantissa,
+                ieee_exponent as i32 - self.bias - self.mantissa_bits as i32,
+            )
+        }
+    }
+}
+
+/// 返回`ceil(log2(5^e))`，`e == 0`时返回`1`。
+#[inline(always)]
+fn pow5bits(e: i32) -> i32 {
+    (((e as u32) * 1217359) >> 19) as i32 + 1
+}
+
+/// 返回`floor(log10(2^e))`。
+#[inline(always)]
+fn log10_pow2(e: i32) -> i32 {
+    (((e as u32) * 78913) >> 18) as i32
+}
+
+/// 返回`floor(log10(5^e))`。
+#[inline(always)]
+fn log10_pow5(e: i32) -> i32 {
+    (((e as u32) * 732923) >> 20) as i32
+}
+
+#[inline(always)]
+fn pow5_factor(mut value: u64) -> u32 {
+    let mut count = 0;
+    while value.is_multiple_of(5) {
+        value /= 5;
+        count += 1;
+    }
+    count
+}
+
+#[inline(always)]
+fn multiple_of_power_of_5(value: u64, p: i32) -> bool {
+    pow5_factor(value) >= p as u32
+}
+
+#[inline(always)]
+fn multiple_of_power_of_2(value: u64, p: i32) -> bool {
+    value.trailing_zeros() >= p as u32
+}
+
+/// 计算`(m * mul) >> j`，其中`mul`是128位的乘数且`j >= 64`。
+#[inline(always)]
+fn mul_shift(m: u64, mul: u128, j: i32) -> u64 {
+    let b0 = (m as u128) * (mul as u64 as u128);
+    let b2 = (m as u128) * (mul >> 64);
+    (((b0 >> 64) + b2) >> (j - 64)) as u64
+}
+
+/// Ryu 算法：返回能往返转换回原值的最短十进制表示`(output, e10)`，
+/// 其值等于`output * 10^e10`。
+fn shortest(format: &Format, ieee_mantissa: u64, ieee_exponent: u32) -> (u64, i32) {
+    let (m2, e2) = format.binary(ieee_mantissa, ieee_exponent);
+    // 多减去2位，以便下面用整数表示区间的中点
+    let e2 = e2 - 2;
+    let even = m2 & 1 == 0;
+    let accept_bounds = even;
+
+    let mv = 4 * m2;
+    let mm_shift = (ieee_mantissa != 0 || ieee_exponent <= 1) as u64;
+
+    let mut vr;
+    let mut vp;
+    let mut vm;
+    let e10;
+    let mut vm_is_trailing_zeros = false;
+    let mut vr_is_trailing_zeros = false;
+
+    if e2 >= 0 {
+        let q = log10_pow2(e2) - (e2 > 3) as i32;
+        e10 = q;
+        let k = POW5_INV_BITCOUNT + pow5bits(q) - 1;
+        let i = -e2 + q + k;
+        let mul = DOUBLE_POW5_INV_SPLIT[q as usize];
+        vr = mul_shift(4 * m2, mul, i);
+        vp = mul_shift(4 * m2 + 2, mul, i);
+        vm = mul_shift(4 * m2 - 1 - mm_shift, mul, i);
+        if q <= 21 {
+            // 三者中至多只有一个是5的倍数
+            if mv % 5 == 0 {
+                vr_is_trailing_zeros = multiple_of_power_of_5(mv, q);
+            } else if accept_bounds {
+                vm_is_trailing_zeros = multiple_of_power_of_5(mv - 1 - mm_shift, q);
+            } else {
+                vp -= multiple_of_power_of_5(mv + 2, q) as u64;
+            }
+        }
+    } else {
+        let q = log10_pow5(-e2) - (-e2 > 1) as i32;
+        e10 = q + e2;
+        let i = -e2 - q;
+        let k = pow5bits(i) - POW5_BITCOUNT;
+        let j = q - k;
+        let mul = DOUBLE_POW5_SPLIT[i as usize];
+        vr = mul_shift(4 * m2, mul, j);
+        vp = mul_shift(4 * m2 + 2, mul, j);
+        vm = mul_shift(4 * m2 - 1 - mm_shift, mul, j);
+        if q <= 1 {
+            // `mv`至少有`q`个尾随零
+            vr_is_trailing_zeros = true;
+            if accept_bounds {
+                vm_is_trailing_zeros = mm_shift == 1;
+            } else {
+                vp -= 1;
+            }
+        } else if q < 63 {
+            vr_is_trailing_zeros = multiple_of_power_of_2(mv, q);
+        }
+    }
+
+    let mut removed = 0;
+    let output = if vm_is_trailing_zeros || vr_is_trailing_zeros {
+        // 一般情况，很少发生
+        let mut last_removed_digit = 0;
+        while vp / 10 > vm / 10 {
+            vm_is_trailing_zeros &= vm % 10 == 0;
+            last_removed_digit = (vr % 10) as u8;
+            vr /= 10;
+            vp /= 10;
+            vm /= 10;
+            removed += 1;
+        }
+        if vm_is_trailing_zeros {
+            while vm % 10 == 0 {
+                last_removed_digit = (vr % 10) as u8;
+                vr /= 10;
+                vm /= 10;
+                removed += 1;
+            }
+        }
+        // 与`core::fmt`一致，恰好在中间时向上舍入（原始的 Ryu 算法在此处向偶数舍入），
+        // 因此这里不再需要`vr`是否有尾随零的信息
+        vr + ((vr == vm && (!accept_bounds || !vm_is_trailing_zeros)) || last_removed_digit >= 5)
+            as u64
+    } else {
+        // 常见情况
+        let mut round_up = false;
+        if vp / 100 > vm / 100 {
+            round_up = vr % 100 >= 50;
+            vr /= 100;
+            vp /= 100;
+            vm /= 100;
+            removed += 2;
+        }
+        while vp / 10 > vm / 10 {
+            round_up = vr % 10 >= 5;
+            vr /= 10;
+            vp /= 10;
+            vm /= 10;
+            removed += 1;
+        }
+        vr + (vr == vm || round_up) as u64
+    };
+
+    (output, e10 + removed)
+}
+
+/// 将浮点数以最短往返表示附加到`s`的末尾。
+pub(crate) fn push_shortest<const SIZE: usize>(
+    s: &mut SmallString<SIZE>,
+    format: &Format,
+    negative: bool,
+    decoded: Decoded,
+) {
+    let (ieee_mantissa, ieee_exponent) = match decoded {
+        Decoded::Nan => return s.push_str("NaN"),
+        Decoded::Infinite => return s.push_str(if negative { "-inf" } else { "inf" }),
+        Decoded::Zero => return s.push_str(if negative { "-0" } else { "0" }),
+        Decoded::Finite {
+            ieee_mantissa,
+            ieee_exponent,
+        } => (ieee_mantissa, ieee_exponent),
+    };
+
+    let (output, e10) = shortest(format, ieee_mantissa, ieee_exponent);
+    let olength = integer::count_digits_u64(output) as i32;
+    let point = olength + e10;
+
+    if negative {
+        s.push('-');
+    }
+
+    if e10 >= 0 {
+        // 整数，在最短的数字后面补零
+        s.reserve(olength as usize + e10 as usize);
+        s.push_integer(output);
+        push_repeat(s, b'0', e10 as usize);
+    } else if point > 0 {
+        // 小数点位于数字中间
+        s.reserve(olength as usize + 1);
+        let start = s.len();
+        s.push_integer(output);
+        s.insert(start + point as usize, '.');
+    } else {
+        // 纯小数，需要在数字前面补零
+        s.reserve(2 + (-point) as usize + olength as usize);
+        s.push_str("0.");
+        push_repeat(s, b'0', (-point) as usize);
+        s.push_integer(output);
+    }
+}
+
+/// 将浮点数以保留`precision`位小数的形式附加到`s`的末尾。
+///
+/// 与`core::fmt`一致，舍入基于二进制值的精确十进制展开，恰好在中间时向偶数舍入。
+pub(crate) fn push_fixed<const SIZE: usize>(
+    s: &mut SmallString<SIZE>,
+    format: &Format,
+    negative: bool,
+    decoded: Decoded,
+    precision: usize,
+) {
+    let (m2, e2) = match decoded {
+        Decoded::Nan => return s.push_str("NaN"),
+        Decoded::Infinite => return s.push_str(if negative { "-inf" } else { "inf" }),
+        Decoded::Zero => (0, 0),
+        Decoded::Finite {
+            ieee_mantissa,
+            ieee_exponent,
+        } => format.binary(ieee_mantissa, ieee_exponent),
+    };
+
+    if negative {
+        s.push('-');
+    }
+
+    if e2 >= 0 {
+        // 没有小数部分
+        let mut big = Big::from_u64(m2);
+        big.shl(e2 as usize);
+        big.push_decimal(s);
+        if precision > 0 {
+            s.reserve(precision + 1);
+            s.push('.');
+            push_repeat(s, b'0', precision);
+        }
+        return;
+    }
+
+    let k = (-e2) as usize;
+    let int_part = if k < 64 { m2 >> k } else { 0 };
+    let mut frac = Big::from_u64(if k < 64 { m2 & ((1 << k) - 1) } else { m2 });
+
+    let start = s.len();
+    s.reserve(integer::count_digits_u64(int_part) + 1 + precision);
+    s.push_integer(int_part);
+
+    if precision > 0 {
+        s.push('.');
+        let mut remaining = precision;
+        while remaining > 0 && !frac.is_zero() {
+            frac.mul_small(10);
+            let digit = frac.take_from(k);
+            unsafe { s.as_mut_vec() }.push(b'0' + digit as u8);
+            remaining -= 1;
+        }
+        push_repeat(s, b'0', remaining);
+    }
+
+    // 剩余部分与`1/2`比较，决定是否进位
+    let bytes = unsafe { s.as_mut_vec() };
+    let last_odd = (bytes[bytes.len() - 1] - b'0') & 1 == 1;
+    let round_up = frac.bit(k - 1) && (last_odd || frac.any_below(k - 1));
+
+    if round_up {
+        let mut i = bytes.len();
+        loop {
+            if i == start {
+                // 所有数字都是9，需要在最前面插入1
+                bytes.insert(start, b'1');
+                break;
+            }
+            i -= 1;
+            match bytes[i] {
+                b'.' => {}
+                b'9' => bytes[i] = b'0',
+                _ => {
+                    bytes[i] += 1;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[inline(always)]
+fn push_repeat<const SIZE: usize>(s: &mut SmallString<SIZE>, byte: u8, count: usize) {
+    let data = unsafe { s.as_mut_vec() };
+    data.resize(data.len() + count, byte);
+}
+
+/// 足以容纳`f64`精确二进制展开的定长大整数，使用小端序的32位limb。
+struct Big {
+    limbs: [u32; Big::LIMBS],
+}
+
+impl Big {
+    /// `f64`的整数值小于`2^1024`，小数部分乘以10后小于`2^1079`。
+    const LIMBS: usize = 34;
+
+    fn from_u64(value: u64) -> Big {
+        let mut limbs = [0; Big::LIMBS];
+        limbs[0] = value as u32;
+        limbs[1] = (value >> 32) as u32;
+        Big { limbs }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|&limb| limb == 0)
+    }
+
+    fn shl(&mut self, bits: usize) {
+        let limbs = bits / 32;
+        let bits = (bits % 32) as u32;
+
+        for i in (0..Big::LIMBS).rev() {
+            let mut value = if i >= limbs {
+                self.limbs[i - limbs] << bits
+            } else {
+                0
+            };
+            if bits > 0 && i > limbs {
+                value |= self.limbs[i - limbs - 1] >> (32 - bits);
+            }
+            self.limbs[i] = value;
+        }
+    }
+
+    fn mul_small(&mut self, factor: u32) {
+        let mut carry = 0;
+        for limb in self.limbs.iter_mut() {
+            let value = (*limb as u64) * (factor as u64) + carry;
+            *limb = value as u32;
+            carry = value >> 32;
+        }
+        debug_assert_eq!(carry, 0);
+    }
+
+    /// 原地除以`divisor`并返回余数。
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut rem = 0;
+        for limb in self.limbs.iter_mut().rev() {
+            let value = (rem << 32) | *limb as u64;
+            *limb = (value / divisor as u64) as u32;
+            rem = value % divisor as u64;
+        }
+        rem as u32
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        (self.limbs[index / 32] >> (index % 32)) & 1 == 1
+    }
+
+    /// 如果低于`index`的位中有任意一位为1，则返回`true`。
+    fn any_below(&self, index: usize) -> bool {
+        let limb = index / 32;
+        let bits = index % 32;
+        self.limbs[..limb].iter().any(|&l| l != 0)
+            || (bits > 0 && self.limbs[limb] & ((1 << bits) - 1) != 0)
+    }
+
+    /// 取出第`index`位及以上的值（必须小于`2^32`），并将这些位清零。
+    fn take_from(&mut self, index: usize) -> u32 {
+        let limb = index / 32;
+        let bits = (index % 32) as u32;
+
+        let mut high = (self.limbs[limb] as u64) >> bits;
+        if limb + 1 < Big::LIMBS {
+            high |= (self.limbs[limb + 1] as u64) << (32 - bits);
+        }
+
+        self.limbs[limb] &= ((1_u64 << bits) - 1) as u32;
+        for l in self.limbs[limb + 1..].iter_mut() {
+            *l = 0;
+        }
+        high as u32
+    }
+
+    /// 以十进制附加到`s`的末尾，每次处理9位数字。
+    fn push_decimal<const SIZE: usize>(mut self, s: &mut SmallString<SIZE>) {
+        // `2^1024`不超过309位十进制数字
+        let mut chunks = [0_u32; 35];
+        let mut count = 0;
+        loop {
+            chunks[count] = self.div_rem_small(1_000_000_000);
+            count += 1;
+            if self.is_zero() {
+                break;
+            }
+        }
+
+        s.reserve(count * 9);
+        s.push_integer(chunks[count - 1]);
+        for &chunk in chunks[..count - 1].iter().rev() {
+            s.push_integer_padded(chunk, 9, '0');
+        }
+    }
+}
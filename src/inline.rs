@@ -0,0 +1,884 @@
+use core::{
+    borrow::{Borrow, BorrowMut},
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt,
+    iter::FromIterator,
+    ops,
+    slice::SliceIndex,
+    str::{Chars, Utf8Error},
+};
+
+use alloc::{borrow::Cow, boxed::Box, string::String};
+
+use crate::{
+    string::slice_range, CapacityError, Drain, IntoChars, SmallInteger, SmallPattern, SmallString,
+    TryReserveError,
+};
+
+const CAPACITY_EXCEEDED: &str = "InlineString capacity exceeded";
+
+/// 任意整数以任意进制表示时的最大字节数（`u128`的二进制表示）。
+const INTEGER_BUF: usize = 128;
+
+/// `f64`最短往返表示的最大字节数，不使用科学计数法时约为 344 字节。
+const SHORTEST_BUF: usize = 384;
+
+/// 任何`f64`的精确十进制展开最多有 1074 位小数，更多的小数位都是`0`。
+const MAX_EXACT_PRECISION: usize = 1074;
+
+/// `f64`保留`MAX_EXACT_PRECISION`位小数时的最大字节数：符号、309 位整数（进位时多一位）、小数点和小数部分。
+const FIXED_BUF: usize = 1 + 310 + 1 + MAX_EXACT_PRECISION;
+
+/// 生成一对格式化方法：先在栈上的临时缓冲区中格式化，确认容量足够后再追加，
+/// 因此永远不会分配内存。`try_*`版本在容量不足时返回错误，另一个版本则 panic。
+macro_rules! push_formatted {
+    ($($(#[$attr:meta])* fn $name:ident, $try_name:ident $(<$t:ident>)?($($arg:ident: $ty:ty),*) in $buf:ident;)*) => {
+        $(
+            $(#[$attr])*
+            ///
+            /// # Panics
+            ///
+            /// 如果容量不足。
+            #[inline]
+            pub fn $name$(<$t: SmallInteger>)?(&mut self, $($arg: $ty),*) {
+                self.$try_name($($arg),*).expect(CAPACITY_EXCEEDED)
+            }
+
+            #[doc = concat!("同 [`", stringify!($name), "`](#method.", stringify!($name), ")，如果容量不足则返回错误，字符串保持不变。")]
+            #[inline]
+            pub fn $try_name$(<$t: SmallInteger>)?(
+                &mut self,
+                $($arg: $ty),*
+            ) -> Result<(), CapacityError> {
+                let mut buf = SmallString::<$buf>::new();
+                buf.$name($($arg),*);
+                self.try_push_str(&buf)
+            }
+        )*
+    };
+}
+
+/// 生成一对补齐的整数格式化方法，参见 [`push_formatted`]。
+macro_rules! push_padded {
+    ($($(#[$attr:meta])* fn $name:ident, $try_name:ident via $unpadded:ident;)*) => {
+        $(
+            $(#[$attr])*
+            ///
+            /// # Panics
+            ///
+            /// 如果容量不足。
+            #[inline]
+            pub fn $name<T: SmallInteger>(&mut self, num: T, width: usize, fill: char) {
+                self.$try_name(num, width, fill).expect(CAPACITY_EXCEEDED)
+            }
+
+            #[doc = concat!("同 [`", stringify!($name), "`](#method.", stringify!($name), ")，如果容量不足则返回错误，字符串保持不变。")]
+            #[inline]
+            pub fn $try_name<T: SmallInteger>(
+                &mut self,
+                num: T,
+                width: usize,
+                fill: char,
+            ) -> Result<(), CapacityError> {
+                let mut buf = SmallString::<INTEGER_BUF>::new();
+                buf.$unpadded(num);
+                self.try_push_padded(&buf, width, fill)
+            }
+        )*
+    };
+}
+
+/// 只使用内联存储、永远不会分配堆内存的定长字符串。
+///
+/// `InlineString`提供与 [`SmallString`] 相同的方法。会增加长度的方法在容量不足时 panic，
+/// 对应的`try_*`方法则返回 [`CapacityError`]，因此在两者之间切换只需要修改类型。
+/// 注意`SmallString`的`try_*`方法会按需分配内存，只在分配失败时返回 [`TryReserveError`]。
+///
+/// # Examples
+///
+/// ```
+/// use smallstr::InlineString;
+///
+/// let mut s: InlineString<4> = InlineString::new();
+///
+/// s.push_str("ab");
+/// assert!(s.try_push_integer(42).is_ok());
+/// assert!(s.try_push('!').is_err());
+///
+/// assert_eq!(s, "ab42");
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InlineString<const N: usize> {
+    inner: SmallString<N>,
+}
+
+impl<const N: usize> InlineString<N> {
+    /// 构造一个空字符串。
+    #[inline(always)]
+    pub const fn new() -> InlineString<N> {
+        InlineString {
+            inner: SmallString::new(),
+        }
+    }
+
+    /// 通过从`&str`复制数据来构造`InlineString`。
+    ///
+    /// 如果`s`的长度超过`N`，则返回错误。
+    #[inline(always)]
+    pub fn try_from_str(s: &str) -> Result<InlineString<N>, CapacityError> {
+        let mut string = InlineString::new();
+        string.try_push_str(s)?;
+        Ok(string)
+    }
+
+    /// 通过从`&str`复制数据来构造`InlineString`。
+    ///
+    /// # Panics
+    ///
+    /// 如果`s`的长度超过`N`。
+    #[inline(always)]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> InlineString<N> {
+        InlineString::try_from_str(s).expect(CAPACITY_EXCEEDED)
+    }
+
+    /// 构造一个空字符串。`InlineString`的容量总是`N`，该方法只检查`n`是否超过`N`。
+    ///
+    /// # Panics
+    ///
+    /// 如果`n`大于`N`。
+    #[inline(always)]
+    pub fn with_capacity(n: usize) -> InlineString<N> {
+        assert!(n <= N, "{}", CAPACITY_EXCEEDED);
+        InlineString::new()
+    }
+
+    /// 使用 UTF-8 字节在堆栈上构造一个新的 `InlineString`。
+    ///
+    /// 如果提供的字节数组不是有效的 UTF-8，则返回错误。
+    #[inline(always)]
+    pub fn from_buf(buf: [u8; N]) -> Result<InlineString<N>, Utf8Error> {
+        SmallString::from_buf(buf).map(|inner| InlineString { inner })
+    }
+
+    /// 使用提供的字节数组在堆栈上构造一个新的`InlineString`，而不检查该数组是否包含有效的 UTF-8。
+    ///
+    /// # Safety
+    ///
+    /// 同 [`SmallString::from_buf_unchecked`]。
+    #[inline(always)]
+    pub unsafe fn from_buf_unchecked(buf: [u8; N]) -> InlineString<N> {
+        InlineString {
+            inner: SmallString::from_buf_unchecked(buf),
+        }
+    }
+
+    /// 使用字节数组的前`len`个字节构造`InlineString`。
+    ///
+    /// 参见 [`SmallString::from_buf_and_len`]。
+    ///
+    /// # Panics
+    ///
+    /// 如果`len`大于`N`。
+    #[inline(always)]
+    pub fn from_buf_and_len(buf: [u8; N], len: usize) -> Result<InlineString<N>, Utf8Error> {
+        SmallString::from_buf_and_len(buf, len).map(|inner| InlineString { inner })
+    }
+
+    /// 使用字节数组的前`len`个字节构造`InlineString`，而不检查长度和 UTF-8。
+    ///
+    /// # Safety
+    ///
+    /// 同 [`SmallString::from_buf_and_len_unchecked`]。
+    #[inline(always)]
+    pub const unsafe fn from_buf_and_len_unchecked(buf: [u8; N], len: usize) -> InlineString<N> {
+        InlineString {
+            inner: SmallString::from_buf_and_len_unchecked(buf, len),
+        }
+    }
+
+    /// 该字符串可以容纳的最大字节数，即`N`。
+    #[inline(always)]
+    pub fn inline_size(&self) -> usize {
+        N
+    }
+
+    /// 返回该字符串的长度（以字节为单位）。
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// 如果此字符串为空，则返回`true`。
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// 返回该字符串可以容纳的字节数，即`N`。
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// 始终返回`false`，`InlineString`的数据永远不会溢出到堆上。
+    #[inline(always)]
+    pub fn spilled(&self) -> bool {
+        false
+    }
+
+    /// 检查剩余容量是否至少为`additional`字节，`InlineString`的容量不会改变。
+    ///
+    /// # Panics
+    ///
+    /// 如果剩余容量不足。
+    #[inline(always)]
+    pub fn reserve(&mut self, additional: usize) {
+        self.check_capacity(additional).expect(CAPACITY_EXCEEDED)
+    }
+
+    /// 同 [`reserve`](#method.reserve)。
+    #[inline(always)]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.reserve(additional)
+    }
+
+    /// 检查剩余容量是否至少为`additional`字节。
+    ///
+    /// 与 [`SmallString::try_reserve`] 的签名相同；剩余容量不足时返回
+    /// [`TryReserveError::CapacityOverflow`]，因为`InlineString`的容量上限就是`N`。
+    #[inline(always)]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.check_capacity(additional)
+            .map_err(|_| TryReserveError::CapacityOverflow)
+    }
+
+    /// 同 [`try_reserve`](#method.try_reserve)。
+    #[inline(always)]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.try_reserve(additional)
+    }
+
+    /// 不做任何事情，`InlineString`的容量总是`N`。
+    #[inline(always)]
+    pub fn shrink_to_fit(&mut self) {}
+
+    /// 不做任何事情，`InlineString`的容量总是`N`。
+    #[inline(always)]
+    pub fn shrink_to(&mut self, _min_capacity: usize) {}
+
+    /// 从字符串中移除指定的字节范围，并以迭代器的形式返回被移除的字符。
+    ///
+    /// 参见 [`SmallString::drain`]。
+    #[inline(always)]
+    pub fn drain<R: ops::RangeBounds<usize>>(&mut self, range: R) -> Drain<'_> {
+        self.inner.drain(range)
+    }
+
+    /// 将给定的`char`附加到该字符串的末尾。
+    ///
+    /// # Panics
+    ///
+    /// 如果容量不足。
+    #[inline(always)]
+    pub fn push(&mut self, ch: char) {
+        self.try_push(ch).expect(CAPACITY_EXCEEDED)
+    }
+
+    /// 将给定的字符串切片附加到该字符串的末尾。
+    ///
+    /// # Panics
+    ///
+    /// 如果容量不足。
+    #[inline(always)]
+    pub fn push_str(&mut self, s: &str) {
+        self.try_push_str(s).expect(CAPACITY_EXCEEDED)
+    }
+
+    /// 将给定的整数以十进制附加到该字符串的末尾。
+    ///
+    /// # Panics
+    ///
+    /// 如果容量不足。
+    #[inline(always)]
+    pub fn push_integer<T: SmallInteger>(&mut self, num: T) {
+        self.try_push_integer(num).expect(CAPACITY_EXCEEDED)
+    }
+
+    push_formatted! {
+        /// 将给定的整数以小写十六进制附加到该字符串的末尾，参见 [`SmallString::push_hex`]。
+        fn push_hex, try_push_hex<T>(num: T) in INTEGER_BUF;
+        /// 将给定的整数以大写十六进制附加到该字符串的末尾。
+        fn push_hex_upper, try_push_hex_upper<T>(num: T) in INTEGER_BUF;
+        /// 将给定的整数以八进制附加到该字符串的末尾。
+        fn push_octal, try_push_octal<T>(num: T) in INTEGER_BUF;
+        /// 将给定的整数以二进制附加到该字符串的末尾。
+        fn push_binary, try_push_binary<T>(num: T) in INTEGER_BUF;
+        /// 将给定的`f64`以最短往返表示附加到该字符串的末尾，参见 [`SmallString::push_f64`]。
+        fn push_f64, try_push_f64(num: f64) in SHORTEST_BUF;
+        /// 将给定的`f32`以最短往返表示附加到该字符串的末尾。
+        fn push_f32, try_push_f32(num: f32) in SHORTEST_BUF;
+    }
+
+    push_padded! {
+        /// 将给定的整数以十进制附加到该字符串的末尾，不足`width`个字符时在左侧用`fill`补齐，
+        /// 参见 [`SmallString::push_integer_padded`]。
+        fn push_integer_padded, try_push_integer_padded via push_integer;
+        /// 同 [`push_hex`](#method.push_hex)，不足`width`个字符时在左侧用`fill`补齐。
+        fn push_hex_padded, try_push_hex_padded via push_hex;
+        /// 同 [`push_hex_upper`](#method.push_hex_upper)，不足`width`个字符时在左侧用`fill`补齐。
+        fn push_hex_upper_padded, try_push_hex_upper_padded via push_hex_upper;
+        /// 同 [`push_octal`](#method.push_octal)，不足`width`个字符时在左侧用`fill`补齐。
+        fn push_octal_padded, try_push_octal_padded via push_octal;
+        /// 同 [`push_binary`](#method.push_binary)，不足`width`个字符时在左侧用`fill`补齐。
+        fn push_binary_padded, try_push_binary_padded via push_binary;
+    }
+
+    /// 将给定的`f64`保留`precision`位小数附加到该字符串的末尾，参见 [`SmallString::push_f64_fixed`]。
+    ///
+    /// # Panics
+    ///
+    /// 如果容量不足。
+    #[inline]
+    pub fn push_f64_fixed(&mut self, num: f64, precision: usize) {
+        self.try_push_f64_fixed(num, precision)
+            .expect(CAPACITY_EXCEEDED)
+    }
+
+    /// 同 [`push_f64_fixed`](#method.push_f64_fixed)，如果容量不足则返回错误，字符串保持不变。
+    #[inline]
+    pub fn try_push_f64_fixed(&mut self, num: f64, precision: usize) -> Result<(), CapacityError> {
+        // 超过`MAX_EXACT_PRECISION`的小数位都是`0`，不需要放进临时缓冲区
+        let exact = precision.min(MAX_EXACT_PRECISION);
+        let mut buf = SmallString::<FIXED_BUF>::new();
+        buf.push_f64_fixed(num, exact);
+        self.try_push_fixed(&buf, num.is_finite(), precision - exact)
+    }
+
+    /// 将给定的`f32`保留`precision`位小数附加到该字符串的末尾。
+    ///
+    /// # Panics
+    ///
+    /// 如果容量不足。
+    #[inline]
+    pub fn push_f32_fixed(&mut self, num: f32, precision: usize) {
+        self.try_push_f32_fixed(num, precision)
+            .expect(CAPACITY_EXCEEDED)
+    }
+
+    /// 同 [`push_f32_fixed`](#method.push_f32_fixed)，如果容量不足则返回错误，字符串保持不变。
+    #[inline]
+    pub fn try_push_f32_fixed(&mut self, num: f32, precision: usize) -> Result<(), CapacityError> {
+        let exact = precision.min(MAX_EXACT_PRECISION);
+        let mut buf = SmallString::<FIXED_BUF>::new();
+        buf.push_f32_fixed(num, exact);
+        self.try_push_fixed(&buf, num.is_finite(), precision - exact)
+    }
+
+    /// 将`char`插入到该字符串的给定字节位置。
+    ///
+    /// # Panics
+    ///
+    /// 如果`index`不在`char`边界上，或者容量不足。
+    #[inline(always)]
+    pub fn insert(&mut self, index: usize, ch: char) {
+        self.try_insert(index, ch).expect(CAPACITY_EXCEEDED)
+    }
+
+    /// 将 `&str` 插入到该字符串的给定字节位置。
+    ///
+    /// # Panics
+    ///
+    /// 如果`index`不在`char`边界上，或者容量不足。
+    #[inline(always)]
+    pub fn insert_str(&mut self, index: usize, s: &str) {
+        self.try_insert_str(index, s).expect(CAPACITY_EXCEEDED)
+    }
+
+    /// 如果容量足够，则将给定的`char`附加到该字符串的末尾，否则返回错误。
+    #[inline(always)]
+    pub fn try_push(&mut self, ch: char) -> Result<(), CapacityError> {
+        self.try_push_str(ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// 如果容量足够，则将给定的字符串切片附加到该字符串的末尾，否则返回错误。
+    #[inline(always)]
+    pub fn try_push_str(&mut self, s: &str) -> Result<(), CapacityError> {
+        self.check_capacity(s.len())?;
+        self.inner.push_str(s);
+        Ok(())
+    }
+
+    /// 如果容量足够，则将给定的整数以十进制附加到该字符串的末尾，否则返回错误。
+    #[inline(always)]
+    pub fn try_push_integer<T: SmallInteger>(&mut self, num: T) -> Result<(), CapacityError> {
+        // 任何整数的十进制表示都不超过40个字节，先在栈上格式化
+        let mut buf = InlineString::<40>::new();
+        buf.inner.push_integer(num);
+        self.try_push_str(&buf)
+    }
+
+    /// 如果容量足够，则将`char`插入到该字符串的给定字节位置，否则返回错误。
+    ///
+    /// # Panics
+    ///
+    /// 如果`index`不在`char`边界上。
+    #[inline(always)]
+    pub fn try_insert(&mut self, index: usize, ch: char) -> Result<(), CapacityError> {
+        self.try_insert_str(index, ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// 如果容量足够，则将`&str`插入到该字符串的给定字节位置，否则返回错误。
+    ///
+    /// # Panics
+    ///
+    /// 如果`index`不在`char`边界上。
+    #[inline(always)]
+    pub fn try_insert_str(&mut self, index: usize, s: &str) -> Result<(), CapacityError> {
+        assert!(self.is_char_boundary(index));
+
+        self.check_capacity(s.len())?;
+        self.inner.insert_str(index, s);
+        Ok(())
+    }
+
+    /// 用给定的字符串替换指定的字节范围，参见 [`SmallString::replace_range`]。
+    ///
+    /// # Panics
+    ///
+    /// 如果范围无效、任一端不在`char`边界上，或者容量不足。
+    #[inline(always)]
+    pub fn replace_range<R: ops::RangeBounds<usize>>(&mut self, range: R, replace_with: &str) {
+        self.try_replace_range(range, replace_with)
+            .expect(CAPACITY_EXCEEDED)
+    }
+
+    /// 如果容量足够，则用给定的字符串替换指定的字节范围，否则返回错误，字符串保持不变。
+    ///
+    /// # Panics
+    ///
+    /// 如果范围无效，或者任一端不在`char`边界上。
+    #[inline(always)]
+    pub fn try_replace_range<R: ops::RangeBounds<usize>>(
+        &mut self,
+        range: R,
+        replace_with: &str,
+    ) -> Result<(), CapacityError> {
+        let (start, end) = slice_range(range, self.len());
+        assert!(self.is_char_boundary(start));
+        assert!(self.is_char_boundary(end));
+
+        if replace_with.len() > end - start {
+            self.check_capacity(replace_with.len() - (end - start))?;
+        }
+        self.inner.replace_range(start..end, replace_with);
+        Ok(())
+    }
+
+    /// 在给定的字节位置将字符串一分为二，返回`[at, len)`部分。
+    ///
+    /// # Panics
+    ///
+    /// 如果`at`超过字符串长度或者不在`char`边界上。
+    #[inline(always)]
+    #[must_use = "use `.truncate()` if you don't need the other half"]
+    pub fn split_off(&mut self, at: usize) -> InlineString<N> {
+        InlineString {
+            inner: self.inner.split_off(at),
+        }
+    }
+
+    /// 在给定的字节位置将字符串一分为二，返回`([0, at), [at, len))`。
+    ///
+    /// # Panics
+    ///
+    /// 如果`at`超过字符串长度或者不在`char`边界上。
+    #[inline(always)]
+    pub fn split_at_owned(mut self, at: usize) -> (InlineString<N>, InlineString<N>) {
+        let other = self.split_off(at);
+        (self, other)
+    }
+
+    /// 就地移除开头和结尾的空白字符。
+    #[inline(always)]
+    pub fn trim_in_place(&mut self) {
+        self.inner.trim_in_place()
+    }
+
+    /// 就地移除开头的空白字符。
+    #[inline(always)]
+    pub fn trim_start_in_place(&mut self) {
+        self.inner.trim_start_in_place()
+    }
+
+    /// 就地移除结尾的空白字符。
+    #[inline(always)]
+    pub fn trim_end_in_place(&mut self) {
+        self.inner.trim_end_in_place()
+    }
+
+    /// 如果字符串以`prefix`开头，则就地移除它并返回`true`。
+    #[inline(always)]
+    pub fn strip_prefix_in_place<P: SmallPattern>(&mut self, prefix: P) -> bool {
+        self.inner.strip_prefix_in_place(prefix)
+    }
+
+    /// 如果字符串以`suffix`结尾，则就地移除它并返回`true`。
+    #[inline(always)]
+    pub fn strip_suffix_in_place<P: SmallPattern>(&mut self, suffix: P) -> bool {
+        self.inner.strip_suffix_in_place(suffix)
+    }
+
+    /// 从此字符串中删除最后一个字符并返回它。
+    ///
+    /// 如果字符串为空，则返回`None`。
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<char> {
+        self.inner.pop()
+    }
+
+    /// 缩短字符串，保留前一个`len`字节。
+    ///
+    /// # Panics
+    ///
+    /// 如果`len`不在`char`边界上。
+    #[inline(always)]
+    pub fn truncate(&mut self, len: usize) {
+        self.inner.truncate(len)
+    }
+
+    /// 提取包含整个字符串的字符串切片。
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        self.inner.as_str()
+    }
+
+    /// 提取包含整个字符串的字符串切片。
+    #[inline(always)]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        self.inner.as_mut_str()
+    }
+
+    /// 删除字符串的所有内容。
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.inner.clear()
+    }
+
+    /// 从此字符串中的字节位置删除`char`并返回它。
+    ///
+    /// # Panics
+    ///
+    /// 如果`index`不在`char`边界上。
+    #[inline(always)]
+    pub fn remove(&mut self, index: usize) -> char {
+        self.inner.remove(index)
+    }
+
+    /// 仅保留谓词指定的字符。
+    #[inline(always)]
+    pub fn retain<F: FnMut(char) -> bool>(&mut self, f: F) {
+        self.inner.retain(f)
+    }
+
+    /// 如果字符串恰好占满`N`个字节，则将其转换为`[u8; N]`。否则，返回`Err(Self)`。
+    #[inline(always)]
+    pub fn into_inner(self) -> Result<[u8; N], InlineString<N>> {
+        self.inner
+            .into_inner()
+            .map_err(|inner| InlineString { inner })
+    }
+
+    /// 将字符串转换为字节数组和有效内容的长度，参见 [`SmallString::into_inline_parts`]。
+    ///
+    /// `InlineString`的长度不会超过`N`，因此总是成功。
+    #[inline(always)]
+    pub fn into_inline_parts(self) -> ([u8; N], usize) {
+        match self.inner.into_inline_parts() {
+            Ok(parts) => parts,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// 将`InlineString`转换为`String`。
+    ///
+    /// 该方法会分配内存，与`InlineString`其余的方法不同。
+    #[inline(always)]
+    pub fn into_string(self) -> String {
+        self.inner.into_string()
+    }
+
+    /// 将`InlineString`转换为`Box<str>`。
+    ///
+    /// 该方法会分配内存，与`InlineString`其余的方法不同。
+    #[inline(always)]
+    pub fn into_boxed_str(self) -> Box<str> {
+        self.inner.into_boxed_str()
+    }
+
+    /// 转换为内容相同的 [`SmallString`]，不会分配内存。
+    #[inline(always)]
+    pub fn into_small_string(self) -> SmallString<N> {
+        self.inner
+    }
+
+    /// 追加`digits`并在左侧补齐到`width`个字符，补齐前先检查容量。
+    fn try_push_padded(
+        &mut self,
+        digits: &str,
+        width: usize,
+        fill: char,
+    ) -> Result<(), CapacityError> {
+        let pad = width.saturating_sub(digits.len());
+        let additional = pad
+            .checked_mul(fill.len_utf8())
+            .and_then(|amt| amt.checked_add(digits.len()))
+            .ok_or(CapacityError)?;
+        self.check_capacity(additional)?;
+
+        // 容量已确认足够，补齐不会重新分配
+        let start = self.len();
+        self.inner.push_str(digits);
+        self.inner.pad_start(start, width, fill);
+        Ok(())
+    }
+
+    /// 追加定点小数`fixed`，有限值再补上`zeros`个超出精确位数的`0`。
+    fn try_push_fixed(
+        &mut self,
+        fixed: &str,
+        finite: bool,
+        zeros: usize,
+    ) -> Result<(), CapacityError> {
+        let zeros = if finite { zeros } else { 0 };
+        self.check_capacity(fixed.len().saturating_add(zeros))?;
+
+        self.inner.push_str(fixed);
+        for _ in 0..zeros {
+            self.inner.push('0');
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn check_capacity(&self, additional: usize) -> Result<(), CapacityError> {
+        if additional > N - self.len() {
+            Err(CapacityError)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<const N: usize> ops::Deref for InlineString<N> {
+    type Target = str;
+
+    #[inline(always)]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> ops::DerefMut for InlineString<N> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for InlineString<N> {
+    #[inline(always)]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsMut<str> for InlineString<N> {
+    #[inline(always)]
+    fn as_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for InlineString<N> {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const N: usize> Borrow<str> for InlineString<N> {
+    #[inline(always)]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> BorrowMut<str> for InlineString<N> {
+    #[inline(always)]
+    fn borrow_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<const N: usize> fmt::Write for InlineString<N> {
+    #[inline(always)]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+
+    #[inline(always)]
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        self.try_push(ch).map_err(|_| fmt::Error)
+    }
+}
+
+impl<const N: usize> fmt::Debug for InlineString<N> {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Display for InlineString<N> {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for InlineString<N> {
+    type Error = CapacityError;
+
+    #[inline(always)]
+    fn try_from(s: &str) -> Result<InlineString<N>, CapacityError> {
+        InlineString::try_from_str(s)
+    }
+}
+
+impl<const N: usize> TryFrom<SmallString<N>> for InlineString<N> {
+    type Error = SmallString<N>;
+
+    /// 如果字符串的长度不超过`N`，则将其移回内联存储，否则原样返回。
+    #[inline(always)]
+    fn try_from(mut s: SmallString<N>) -> Result<InlineString<N>, SmallString<N>> {
+        if s.len() > N {
+            return Err(s);
+        }
+        s.shrink_to_fit();
+        Ok(InlineString { inner: s })
+    }
+}
+
+impl<const N: usize> From<InlineString<N>> for SmallString<N> {
+    #[inline(always)]
+    fn from(s: InlineString<N>) -> SmallString<N> {
+        s.into_small_string()
+    }
+}
+
+impl<I: SliceIndex<str>, const N: usize> ops::Index<I> for InlineString<N> {
+    type Output = I::Output;
+
+    #[inline(always)]
+    fn index(&self, index: I) -> &I::Output {
+        &self.as_str()[index]
+    }
+}
+
+impl<I: SliceIndex<str>, const N: usize> ops::IndexMut<I> for InlineString<N> {
+    #[inline(always)]
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        &mut self.as_mut_str()[index]
+    }
+}
+
+impl<const N: usize> IntoIterator for InlineString<N> {
+    type Item = char;
+    type IntoIter = IntoChars<N>;
+
+    #[inline(always)]
+    fn into_iter(self) -> IntoChars<N> {
+        self.inner.into_iter()
+    }
+}
+
+impl<'a, const N: usize> IntoIterator for &'a InlineString<N> {
+    type Item = char;
+    type IntoIter = Chars<'a>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Chars<'a> {
+        self.chars()
+    }
+}
+
+/// 容量不足时 panic，与 [`InlineString::push_str`] 一致。
+macro_rules! impl_extend {
+    ($($item:ty => |$s:ident, $x:ident| $push:expr;)*) => {
+        $(
+            impl<'a, const N: usize> Extend<$item> for InlineString<N> {
+                fn extend<I: IntoIterator<Item = $item>>(&mut self, iter: I) {
+                    let $s = self;
+                    for $x in iter {
+                        $push;
+                    }
+                }
+            }
+
+            impl<'a, const N: usize> FromIterator<$item> for InlineString<N> {
+                fn from_iter<I: IntoIterator<Item = $item>>(iter: I) -> InlineString<N> {
+                    let mut s = InlineString::new();
+                    s.extend(iter);
+                    s
+                }
+            }
+        )*
+    };
+}
+
+impl_extend! {
+    char => |s, ch| s.push(ch);
+    &'a char => |s, ch| s.push(*ch);
+    &'a str => |s, x| s.push_str(x);
+    String => |s, x| s.push_str(&x);
+    Cow<'a, str> => |s, x| s.push_str(&x);
+}
+
+macro_rules! eq_str {
+    ( $rhs:ty ) => {
+        impl<'a, const N: usize> PartialEq<$rhs> for InlineString<N> {
+            #[inline(always)]
+            fn eq(&self, rhs: &$rhs) -> bool {
+                self[..] == rhs[..]
+            }
+        }
+
+        impl<'a, const N: usize> PartialEq<InlineString<N>> for $rhs {
+            #[inline(always)]
+            fn eq(&self, rhs: &InlineString<N>) -> bool {
+                self[..] == rhs[..]
+            }
+        }
+
+        impl<'a, const N: usize> PartialOrd<$rhs> for InlineString<N> {
+            #[inline(always)]
+            fn partial_cmp(&self, rhs: &$rhs) -> Option<Ordering> {
+                Some(self[..].cmp(&rhs[..]))
+            }
+        }
+
+        impl<'a, const N: usize> PartialOrd<InlineString<N>> for $rhs {
+            #[inline(always)]
+            fn partial_cmp(&self, rhs: &InlineString<N>) -> Option<Ordering> {
+                Some(self[..].cmp(&rhs[..]))
+            }
+        }
+    };
+}
+
+eq_str!(str);
+eq_str!(&'a str);
+eq_str!(String);
+eq_str!(Cow<'a, str>);
@@ -0,0 +1,186 @@
+use crate::SmallString;
+
+/// `10^0`..=`10^19`，`u64`能表示的所有10的幂。
+const POW10_U64: [u64; 20] = pow10_u64();
+
+/// `10^0`..=`10^38`，`u128`能表示的所有10的幂。
+const POW10_U128: [u128; 39] = pow10_u128();
+
+const fn pow10_u64() -> [u64; 20] {
+    let mut table = [1; 20];
+    let mut i = 1;
+    while i < table.len() {
+        table[i] = table[i - 1] * 10;
+        i += 1;
+    }
+    table
+}
+
+const fn pow10_u128() -> [u128; 39] {
+    let mut table = [1; 39];
+    let mut i = 1;
+    while i < table.len() {
+        table[i] = table[i - 1] * 10;
+        i += 1;
+    }
+    table
+}
+
+/// 返回`num`的十进制位数。
+///
+/// 只使用整数运算：先根据二进制位数估算位数（`1233 / 4096`约等于`log10(2)`），
+/// 再与对应的10的幂比较修正，因此在任何值上都是精确的。
+#[inline(always)]
+pub(crate) fn count_digits_u64(num: u64) -> usize {
+    let bits = 64 - (num | 1).leading_zeros() as usize;
+    let t = (bits * 1233) >> 12;
+    // `0`也占一位
+    (t + (num >= POW10_U64[t]) as usize).max(1)
+}
+
+/// 返回`num`的十进制位数，算法同 [`count_digits_u64`]。
+#[inline(always)]
+pub(crate) fn count_digits_u128(num: u128) -> usize {
+    let bits = 128 - (num | 1).leading_zeros() as usize;
+    let t = (bits * 1233) >> 12;
+    (t + (num >= POW10_U128[t]) as usize).max(1)
+}
+
+/// 十六进制（以及八进制、二进制）使用的小写数字。
+pub(crate) const DIGITS_LOWER: &[u8; 16] = b"0123456789abcdef";
+
+/// 十六进制使用的大写数字。
+pub(crate) const DIGITS_UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+/// 可以通过 [`SmallString::push_integer`] 以十进制附加到字符串末尾的整数类型。
+///
+/// 该特征已为所有内置整数类型实现，不能在本 crate 之外实现。
+pub trait SmallInteger: sealed::Radix {
+    /// 将`self`以十进制附加到`s`的末尾。
+    fn push_to<const SIZE: usize>(self, s: &mut SmallString<SIZE>);
+}
+
+mod sealed {
+    use crate::SmallString;
+
+    /// [`SmallInteger`](super::SmallInteger) 的私有父特征，提供十六进制等格式使用的实现细节。
+    pub trait Radix: Copy {
+        /// 将`self`的位模式以`2^shift`进制附加到`s`的末尾，每一位数字从`digits`中取得，
+        /// `shift`必须在`1..=4`之内。
+        ///
+        /// 与`core::fmt`一致，有符号整数按二进制补码输出，不带符号。
+        fn push_radix_to<const SIZE: usize>(
+            self,
+            s: &mut SmallString<SIZE>,
+            shift: u32,
+            digits: &[u8; 16],
+        );
+    }
+}
+
+macro_rules! impl_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl SmallInteger for $ty {
+                #[inline(always)]
+                fn push_to<const SIZE: usize>(self, s: &mut SmallString<SIZE>) {
+                    s.push_u64(self as u64);
+                }
+            }
+
+            impl sealed::Radix for $ty {
+                #[inline(always)]
+                fn push_radix_to<const SIZE: usize>(
+                    self,
+                    s: &mut SmallString<SIZE>,
+                    shift: u32,
+                    digits: &[u8; 16],
+                ) {
+                    s.push_radix_u64(self as u64, shift, digits);
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_signed {
+    ($($ty:ty => $unsigned:ty),*) => {
+        $(
+            impl SmallInteger for $ty {
+                #[inline(always)]
+                fn push_to<const SIZE: usize>(self, s: &mut SmallString<SIZE>) {
+                    if self < 0 {
+                        s.push('-');
+                    }
+                    // `unsigned_abs`可以正确处理`MIN`
+                    s.push_u64(self.unsigned_abs() as u64);
+                }
+            }
+
+            impl sealed::Radix for $ty {
+                #[inline(always)]
+                fn push_radix_to<const SIZE: usize>(
+                    self,
+                    s: &mut SmallString<SIZE>,
+                    shift: u32,
+                    digits: &[u8; 16],
+                ) {
+                    s.push_radix_u64(self as $unsigned as u64, shift, digits);
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, usize);
+impl_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize);
+
+impl SmallInteger for u128 {
+    #[inline(always)]
+    fn push_to<const SIZE: usize>(self, s: &mut SmallString<SIZE>) {
+        // 能放进`u64`的值走更快的64位除法
+        if self <= u64::MAX as u128 {
+            s.push_u64(self as u64);
+        } else {
+            s.push_u128(self);
+        }
+    }
+}
+
+impl sealed::Radix for u128 {
+    #[inline(always)]
+    fn push_radix_to<const SIZE: usize>(
+        self,
+        s: &mut SmallString<SIZE>,
+        shift: u32,
+        digits: &[u8; 16],
+    ) {
+        if self <= u64::MAX as u128 {
+            s.push_radix_u64(self as u64, shift, digits);
+        } else {
+            s.push_radix_u128(self, shift, digits);
+        }
+    }
+}
+
+impl SmallInteger for i128 {
+    #[inline(always)]
+    fn push_to<const SIZE: usize>(self, s: &mut SmallString<SIZE>) {
+        if self < 0 {
+            s.push('-');
+        }
+        self.unsigned_abs().push_to(s);
+    }
+}
+
+impl sealed::Radix for i128 {
+    #[inline(always)]
+    fn push_radix_to<const SIZE: usize>(
+        self,
+        s: &mut SmallString<SIZE>,
+        shift: u32,
+        digits: &[u8; 16],
+    ) {
+        sealed::Radix::push_radix_to(self as u128, s, shift, digits);
+    }
+}
@@ -0,0 +1,57 @@
+//! 不检查溢出的整数运算。
+//!
+//! 启用`nightly`功能时直接使用`core::intrinsics`，否则退化为普通的算术运算，
+//! 以便在稳定版 Rust 上编译。
+
+#[cfg(feature = "nightly")]
+pub(crate) use core::intrinsics::{
+    unchecked_add, unchecked_div, unchecked_mul, unchecked_rem, unchecked_sub,
+};
+
+#[cfg(not(feature = "nightly"))]
+pub(crate) use self::fallback::*;
+
+#[cfg(not(feature = "nightly"))]
+mod fallback {
+    use core::ops::{Add, Div, Mul, Rem, Sub};
+
+    /// # Safety
+    ///
+    /// 调用者必须保证结果不会溢出。
+    #[inline(always)]
+    pub(crate) unsafe fn unchecked_add<T: Add<Output = T>>(x: T, y: T) -> T {
+        x + y
+    }
+
+    /// # Safety
+    ///
+    /// 调用者必须保证结果不会溢出。
+    #[inline(always)]
+    pub(crate) unsafe fn unchecked_sub<T: Sub<Output = T>>(x: T, y: T) -> T {
+        x - y
+    }
+
+    /// # Safety
+    ///
+    /// 调用者必须保证结果不会溢出。
+    #[inline(always)]
+    pub(crate) unsafe fn unchecked_mul<T: Mul<Output = T>>(x: T, y: T) -> T {
+        x * y
+    }
+
+    /// # Safety
+    ///
+    /// 调用者必须保证`y`不为零且结果不会溢出。
+    #[inline(always)]
+    pub(crate) unsafe fn unchecked_div<T: Div<Output = T>>(x: T, y: T) -> T {
+        x / y
+    }
+
+    /// # Safety
+    ///
+    /// 调用者必须保证`y`不为零且结果不会溢出。
+    #[inline(always)]
+    pub(crate) unsafe fn unchecked_rem<T: Rem<Output = T>>(x: T, y: T) -> T {
+        x % y
+    }
+}
@@ -1,11 +1,10 @@
-#![allow(internal_features)]
-#![feature(core_intrinsics)]
-#![feature(str_from_raw_parts)]
+#![cfg_attr(feature = "nightly", allow(internal_features))]
+#![cfg_attr(feature = "nightly", feature(core_intrinsics, str_from_raw_parts))]
 //! 实现 `SmallString`，一个类似 `String` 的小字符串容器
 //!
 //! ## `no_std` support
 //!
-//! 默认情况下，`smallstr`不依赖于`std`。 `std`功能可能已启用！添加 `std` 依赖项。 `ffi`功能也意味着`std`。
+//! 默认情况下，`smallstr`不依赖于`std`，只使用`core`和`alloc`。启用`std`功能会添加`std`依赖项。`ffi`功能也意味着`std`。
 //!
 //! ## `ffi` feature
 //!
@@ -16,8 +15,34 @@
 //! * `PartialEq<OsString>`
 //! * `PartialEq<Cow<'_, OsString>>`
 //!
+//! 以及交换左右两侧的`PartialEq`和两个方向的`PartialOrd`实现。
+//!
 //! 此功能还添加`std`作为依赖项。
 //!
+//! 与 C 代码交互时不需要该功能：`SmallCString`只依赖`core::ffi::CStr`，
+//! 可以在`no_std`环境中使用，它把末尾的 NUL 与内容存放在同一个内联缓冲区中。
+//!
+//! ## `nightly` feature
+//!
+//! 默认情况下，`smallstr`可以使用稳定版 Rust 编译。启用`nightly`功能后，
+//! 会使用`core::intrinsics`中不检查溢出的整数运算和`str::from_raw_parts`，
+//! 并启用`smallvec`的`may_dangle`和`specialization`功能。该功能需要 nightly 编译器。
+//!
+//! ## `simd-utf8` feature
+//!
+//! 启用`simd-utf8`功能后，`from_buf`、`from_utf8`等所有检查 UTF-8 的字节构造函数改用
+//! [`simdutf8`](https://docs.rs/simdutf8) 进行校验，返回的`Utf8Error`与`core::str::from_utf8`相同。
+//! 同时启用`std`时会在运行时检测 CPU 是否支持所需的指令，否则只使用编译时启用的目标特性。
+//!
+//! 默认情况下禁用此功能。
+//!
+//! ## `hashbrown` feature
+//!
+//! 启用`hashbrown`功能后会提供`AnySize`键包装，用于以任意`SIZE`的`SmallString`
+//! 查询以另一个`SIZE`为键的`hashbrown::HashMap`或`HashSet`。
+//!
+//! 默认情况下禁用此功能。
+//!
 //! ## `serde` 支持
 //!
 //! 当启用 `serde` 功能时，特征 `serde::Deserialize` 和 ! `serde::Serialize` 是为 `SmallString` 实现的。
@@ -26,10 +51,26 @@
 //!
 //! 默认情况下，`serde`依赖项是使用`no_std`编译的。 ！如果启用了`std`功能，`std`也会作为依赖项添加到`serde`中。
 
-#![cfg_attr(not(any(feature = "ffi", feature = "std")), no_std)]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 extern crate alloc;
 
+pub use compact::*;
+pub use cstring::*;
+pub use error::*;
+pub use inline::*;
+pub use integer::*;
+pub use pattern::*;
 pub use string::*;
 
+mod compact;
+mod cstring;
+mod error;
+mod float;
+mod inline;
+mod integer;
+mod intrinsics;
+mod pattern;
 mod string;
+mod unicode;
+mod utf8;
@@ -0,0 +1,191 @@
+use alloc::string::String;
+
+/// 可以传给 [`SmallString::replace`] 等方法的模式。
+///
+/// 与`str::replace`接受的模式一致，该特征已为`char`、`&str`、`&String`、`&[char]`、`[char; N]`
+/// 以及`FnMut(char) -> bool`闭包实现。
+///
+/// 自定义实现返回的范围和长度必须位于`haystack`的`char`边界上，`min_len`不能超过实际匹配的长度，
+/// 否则使用该模式的方法会 panic。
+///
+/// [`SmallString::replace`]: struct.SmallString.html#method.replace
+pub trait SmallPattern {
+    /// 返回`haystack`中第一个匹配的字节范围`(start, end)`。
+    fn find_in(&mut self, haystack: &str) -> Option<(usize, usize)>;
+
+    /// 如果`haystack`以该模式开头，返回匹配的字节数。
+    fn prefix_len_in(&mut self, haystack: &str) -> Option<usize>;
+
+    /// 如果`haystack`以该模式结尾，返回匹配的字节数。
+    fn suffix_len_in(&mut self, haystack: &str) -> Option<usize>;
+
+    /// 任意一个匹配至少包含的字节数。
+    fn min_len(&self) -> usize;
+}
+
+impl SmallPattern for char {
+    #[inline(always)]
+    fn find_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+        let len = self.len_utf8();
+        haystack.find(*self).map(|start| (start, start + len))
+    }
+
+    #[inline(always)]
+    fn prefix_len_in(&mut self, haystack: &str) -> Option<usize> {
+        if haystack.starts_with(*self) {
+            Some(self.len_utf8())
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    fn suffix_len_in(&mut self, haystack: &str) -> Option<usize> {
+        if haystack.ends_with(*self) {
+            Some(self.len_utf8())
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    fn min_len(&self) -> usize {
+        self.len_utf8()
+    }
+}
+
+impl SmallPattern for &str {
+    #[inline(always)]
+    fn find_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+        haystack
+            .find(*self)
+            .map(|start| (start, start + self.len()))
+    }
+
+    #[inline(always)]
+    fn prefix_len_in(&mut self, haystack: &str) -> Option<usize> {
+        if haystack.starts_with(*self) {
+            Some(self.len())
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    fn suffix_len_in(&mut self, haystack: &str) -> Option<usize> {
+        if haystack.ends_with(*self) {
+            Some(self.len())
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    fn min_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl SmallPattern for &String {
+    #[inline(always)]
+    fn find_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+        self.as_str().find_in(haystack)
+    }
+
+    #[inline(always)]
+    fn prefix_len_in(&mut self, haystack: &str) -> Option<usize> {
+        self.as_str().prefix_len_in(haystack)
+    }
+
+    #[inline(always)]
+    fn suffix_len_in(&mut self, haystack: &str) -> Option<usize> {
+        self.as_str().suffix_len_in(haystack)
+    }
+
+    #[inline(always)]
+    fn min_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl SmallPattern for &[char] {
+    #[inline(always)]
+    fn find_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+        let chars: &[char] = self;
+        find_char(haystack, |ch| chars.contains(&ch))
+    }
+
+    #[inline(always)]
+    fn prefix_len_in(&mut self, haystack: &str) -> Option<usize> {
+        let chars: &[char] = self;
+        char_len_if(haystack.chars().next(), |ch| chars.contains(&ch))
+    }
+
+    #[inline(always)]
+    fn suffix_len_in(&mut self, haystack: &str) -> Option<usize> {
+        let chars: &[char] = self;
+        char_len_if(haystack.chars().next_back(), |ch| chars.contains(&ch))
+    }
+
+    #[inline(always)]
+    fn min_len(&self) -> usize {
+        self.iter().map(|ch| ch.len_utf8()).min().unwrap_or(1)
+    }
+}
+
+impl<const N: usize> SmallPattern for [char; N] {
+    #[inline(always)]
+    fn find_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+        (&self[..]).find_in(haystack)
+    }
+
+    #[inline(always)]
+    fn prefix_len_in(&mut self, haystack: &str) -> Option<usize> {
+        (&self[..]).prefix_len_in(haystack)
+    }
+
+    #[inline(always)]
+    fn suffix_len_in(&mut self, haystack: &str) -> Option<usize> {
+        (&self[..]).suffix_len_in(haystack)
+    }
+
+    #[inline(always)]
+    fn min_len(&self) -> usize {
+        (&self[..]).min_len()
+    }
+}
+
+impl<F: FnMut(char) -> bool> SmallPattern for F {
+    #[inline(always)]
+    fn find_in(&mut self, haystack: &str) -> Option<(usize, usize)> {
+        find_char(haystack, self)
+    }
+
+    #[inline(always)]
+    fn prefix_len_in(&mut self, haystack: &str) -> Option<usize> {
+        char_len_if(haystack.chars().next(), self)
+    }
+
+    #[inline(always)]
+    fn suffix_len_in(&mut self, haystack: &str) -> Option<usize> {
+        char_len_if(haystack.chars().next_back(), self)
+    }
+
+    #[inline(always)]
+    fn min_len(&self) -> usize {
+        1
+    }
+}
+
+#[inline(always)]
+fn find_char<F: FnMut(char) -> bool>(haystack: &str, mut f: F) -> Option<(usize, usize)> {
+    haystack
+        .char_indices()
+        .find(|&(_, ch)| f(ch))
+        .map(|(start, ch)| (start, start + ch.len_utf8()))
+}
+
+#[inline(always)]
+fn char_len_if<F: FnMut(char) -> bool>(ch: Option<char>, mut f: F) -> Option<usize> {
+    ch.filter(|&ch| f(ch)).map(char::len_utf8)
+}
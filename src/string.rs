@@ -1,14 +1,18 @@
 use core::{
     borrow::{Borrow, BorrowMut},
+    char::ToLowercase,
     cmp::Ordering,
+    convert::TryFrom,
     fmt,
     hash::{Hash, Hasher},
-    iter::FromIterator,
-    ops, ptr, slice,
+    iter::{FromIterator, FusedIterator},
+    mem::MaybeUninit,
+    ops, ptr,
+    slice::{self, SliceIndex},
     str::{self, Chars, Utf8Error},
 };
 
-use alloc::{borrow::Cow, boxed::Box, string::String};
+use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
 
 #[cfg(feature = "ffi")]
 use std::ffi::{OsStr, OsString};
@@ -20,17 +24,94 @@ use serde::{
     de::{Deserialize, Deserializer, Error, Visitor},
     ser::{Serialize, Serializer},
 };
-use std::intrinsics::{unchecked_add, unchecked_div, unchecked_mul, unchecked_rem, unchecked_sub};
-// use std::ops::Add;
 
 use smallvec::SmallVec;
 
+use crate::{
+    float, integer,
+    intrinsics::{unchecked_add, unchecked_div, unchecked_mul, unchecked_rem, unchecked_sub},
+    unicode, utf8, FromUtf16Error, FromUtf8Error, SmallInteger, SmallPattern, TryReserveError,
+};
+
+/// 生成将无符号整数以十进制写入字符串末尾的方法，每次借助`DIGIT_PAIRS`处理两位数字。
+macro_rules! push_unsigned {
+    ($name:ident, $ty:ty, $count:path) => {
+        #[inline(always)]
+        pub(crate) fn $name(&mut self, mut num: $ty) {
+            // 我们计算出num的个位数数量
+            let num_len = $count(num);
+
+            // 预留足够的空间，确保后续写入不会越界
+            self.reserve(num_len);
+            let len = self.data.len();
+
+            // 如果是num是奇数我们先处理最后一位
+            if num_len & 1 == 1 {
+                unsafe {
+                    self.data
+                        .as_mut_ptr()
+                        .add(unchecked_sub(unchecked_add(len, num_len), 1))
+                        .write(unchecked_add(b'0', (num % 10) as u8));
+                    num = unchecked_div(num, 10);
+                }
+            }
+
+            for i in (0..(num_len & !1) >> 1).rev() {
+                unsafe {
+                    let pos = unchecked_add(len, unchecked_mul(i, 2));
+                    let digit_pos = (unchecked_rem(num, 100) << 1) as usize;
+
+                    // 每次复制两个字节；两端都不保证按`u16`对齐，因此按字节复制，
+                    // 编译器会将其优化为一次非对齐的 16 位读写
+                    ptr::copy_nonoverlapping(
+                        Self::DIGIT_PAIRS.as_ptr().add(digit_pos),
+                        self.data.as_mut_ptr().add(pos),
+                        2,
+                    );
+                    num = unchecked_div(num, 100);
+                }
+            }
+            // 最后我们需要调整数组的真实长度
+            unsafe { self.data.set_len(len + num_len) }
+        }
+    };
+}
+
+/// 生成将无符号整数以`2^shift`进制写入字符串末尾的方法。
+macro_rules! push_radix {
+    ($name:ident, $ty:ty) => {
+        #[inline(always)]
+        pub(crate) fn $name(&mut self, mut num: $ty, shift: u32, digits: &[u8; 16]) {
+            debug_assert!((1..=4).contains(&shift));
+
+            // 有效位数（`0`按一位计算）除以每个数字占用的位数并向上取整
+            let bits = <$ty>::BITS - (num | 1).leading_zeros();
+            let num_len = bits.div_ceil(shift) as usize;
+            let mask = (1 << shift) - 1;
+
+            self.reserve(num_len);
+            let len = self.data.len();
+
+            unsafe {
+                let ptr = self.data.as_mut_ptr().add(len);
+                for i in (0..num_len).rev() {
+                    ptr.add(i).write(digits[(num & mask) as usize]);
+                    num >>= shift;
+                }
+                self.data.set_len(len + num_len);
+            }
+        }
+    };
+}
+
 /// 类似`String`的容器，可以内联存储少量字节。
 ///
 /// `SmallString` 使用 `SmallVec<[u8; 4096]>` 作为其内部存储。
+///
+/// 不同`SIZE`的`SmallString`之间可以直接比较，哈希值也只取决于字符串内容。
 #[derive(Clone, Default)]
 pub struct SmallString<const SIZE: usize = { const { 1 << 12 } }> {
-    data: SmallVec<u8, SIZE>,
+    pub(crate) data: SmallVec<u8, SIZE>,
 }
 
 impl<const SIZE: usize> SmallString<SIZE> {
@@ -65,12 +146,34 @@ impl<const SIZE: usize> SmallString<SIZE> {
 
     /// 通过从`&str`复制数据来构造`SmallString`。
     #[inline(always)]
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> SmallString<SIZE> {
         SmallString {
-            data: SmallVec::from_slice(s.as_bytes()),
+            data: SmallVec::from(s.as_bytes()),
         }
     }
 
+    /// 通过从`&str`复制数据来构造`SmallString`。
+    ///
+    /// 与 [`from_str`](#method.from_str) 不同，如果分配内存失败，该方法会返回错误而不是中止程序。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::{SmallString, TryReserveError};
+    ///
+    /// let s: SmallString<4> = SmallString::try_from_str("foobar")?;
+    ///
+    /// assert_eq!(s, "foobar");
+    /// # Ok::<(), TryReserveError>(())
+    /// ```
+    #[inline(always)]
+    pub fn try_from_str(s: &str) -> Result<SmallString<SIZE>, TryReserveError> {
+        let mut string = SmallString::new();
+        string.try_push_str(s)?;
+        Ok(string)
+    }
+
     /// 使用现有分配构造`SmallString`。
     #[inline(always)]
     pub fn from_string(s: String) -> SmallString<SIZE> {
@@ -79,6 +182,112 @@ impl<const SIZE: usize> SmallString<SIZE> {
         }
     }
 
+    /// 将字节缓冲区转换为`SmallString`，不复制数据。
+    ///
+    /// 缓冲区可以只填充了一部分，也可以已经溢出到堆中。如果字节不是有效的 UTF-8，
+    /// 返回的错误中包含原始缓冲区。
+    #[inline(always)]
+    pub fn from_utf8(vec: SmallVec<u8, SIZE>) -> Result<SmallString<SIZE>, FromUtf8Error<SIZE>> {
+        match utf8::from_utf8(&vec) {
+            Ok(_) => Ok(SmallString { data: vec }),
+            Err(error) => Err(FromUtf8Error { bytes: vec, error }),
+        }
+    }
+
+    /// 将字节缓冲区转换为`SmallString`，而不检查其是否包含有效的 UTF-8。
+    ///
+    /// # Safety
+    ///
+    /// `vec`必须是有效的 UTF-8，原因同 [`from_buf_unchecked`]。
+    ///
+    /// [`from_buf_unchecked`]: #method.from_buf_unchecked
+    #[inline(always)]
+    pub unsafe fn from_utf8_unchecked(vec: SmallVec<u8, SIZE>) -> SmallString<SIZE> {
+        SmallString { data: vec }
+    }
+
+    /// 将字节切片转换为`SmallString`，无效的 UTF-8 序列被替换为`U+FFFD REPLACEMENT CHARACTER`。
+    ///
+    /// 与`String::from_utf8_lossy`不同，结果直接写入`SmallString`，
+    /// 在内联容量足够时不会产生堆分配。
+    pub fn from_utf8_lossy(v: &[u8]) -> SmallString<SIZE> {
+        // 已校验的前缀整体复制，只对剩余部分逐块替换，不会重复校验
+        let (valid, rest) = v.split_at(utf8::valid_up_to(v));
+        let mut s = SmallString::with_capacity(v.len());
+        s.push_str(unsafe { str::from_utf8_unchecked(valid) });
+
+        for chunk in rest.utf8_chunks() {
+            s.push_str(chunk.valid());
+            if !chunk.invalid().is_empty() {
+                s.push(char::REPLACEMENT_CHARACTER);
+            }
+        }
+
+        s
+    }
+
+    /// 将 UTF-16 编码的切片解码为`SmallString`。
+    ///
+    /// 结果直接写入`SmallString`，在内联容量足够时不会产生堆分配。
+    /// 如果`v`包含未配对的代理项，则返回错误。
+    #[inline(always)]
+    pub fn from_utf16(v: &[u16]) -> Result<SmallString<SIZE>, FromUtf16Error> {
+        SmallString::decode_utf16(v.iter().copied(), v.len())
+    }
+
+    /// 将 UTF-16 编码的切片解码为`SmallString`，未配对的代理项被替换为`U+FFFD REPLACEMENT CHARACTER`。
+    pub fn from_utf16_lossy(v: &[u16]) -> SmallString<SIZE> {
+        let mut s = SmallString::with_capacity(v.len());
+
+        for ch in char::decode_utf16(v.iter().copied()) {
+            s.push(ch.unwrap_or(char::REPLACEMENT_CHARACTER));
+        }
+
+        s
+    }
+
+    /// 将小端序 UTF-16 字节解码为`SmallString`，例如 Windows 事件日志中的字符串。
+    ///
+    /// 如果字节数为奇数或包含未配对的代理项，则返回错误。
+    #[inline(always)]
+    pub fn from_utf16le_bytes(v: &[u8]) -> Result<SmallString<SIZE>, FromUtf16Error> {
+        let chunks = v.chunks_exact(2);
+        if !chunks.remainder().is_empty() {
+            return Err(FromUtf16Error::OddLength);
+        }
+
+        let units = chunks.map(|b| u16::from_le_bytes([b[0], b[1]]));
+        SmallString::decode_utf16(units, v.len() / 2)
+    }
+
+    /// 将大端序 UTF-16 字节解码为`SmallString`。
+    ///
+    /// 如果字节数为奇数或包含未配对的代理项，则返回错误。
+    #[inline(always)]
+    pub fn from_utf16be_bytes(v: &[u8]) -> Result<SmallString<SIZE>, FromUtf16Error> {
+        let chunks = v.chunks_exact(2);
+        if !chunks.remainder().is_empty() {
+            return Err(FromUtf16Error::OddLength);
+        }
+
+        let units = chunks.map(|b| u16::from_be_bytes([b[0], b[1]]));
+        SmallString::decode_utf16(units, v.len() / 2)
+    }
+
+    /// 解码 UTF-16 代码单元，`len`为代码单元的个数，也是 UTF-8 长度的下界。
+    fn decode_utf16<I: Iterator<Item = u16>>(
+        units: I,
+        len: usize,
+    ) -> Result<SmallString<SIZE>, FromUtf16Error> {
+        let mut s = SmallString::with_capacity(len);
+
+        for ch in char::decode_utf16(units) {
+            s.push(ch.map_err(|_| FromUtf16Error::LoneSurrogate)?);
+        }
+
+        Ok(s)
+    }
+
     /// 使用 UTF-8 字节在堆栈上构造一个新的 `SmallString`。
     ///
     /// 如果提供的字节数组不是有效的 UTF-8，则返回错误。
@@ -86,7 +295,7 @@ impl<const SIZE: usize> SmallString<SIZE> {
     pub fn from_buf(buf: [u8; SIZE]) -> Result<SmallString<SIZE>, Utf8Error> {
         let data = SmallVec::from_buf(buf);
 
-        match str::from_utf8(&data) {
+        match utf8::from_utf8(&data) {
             Ok(_) => Ok(SmallString { data }),
             Err(error) => Err(error),
         }
@@ -105,6 +314,37 @@ impl<const SIZE: usize> SmallString<SIZE> {
         }
     }
 
+    /// 使用字节数组的前`len`个字节在堆栈上构造一个新的`SmallString`。
+    ///
+    /// 适用于只填充了一部分的定长缓冲区，例如带有长度字段的`[u8; 32]`名称。
+    /// 如果`buf[..len]`不是有效的 UTF-8，则返回错误。
+    ///
+    /// # Panics
+    ///
+    /// 如果`len`大于`SIZE`。
+    #[inline(always)]
+    pub fn from_buf_and_len(buf: [u8; SIZE], len: usize) -> Result<SmallString<SIZE>, Utf8Error> {
+        assert!(len <= SIZE);
+        utf8::from_utf8(&buf[..len])?;
+
+        Ok(unsafe { SmallString::from_buf_and_len_unchecked(buf, len) })
+    }
+
+    /// 使用字节数组的前`len`个字节在堆栈上构造一个新的`SmallString`，而不检查长度和 UTF-8。
+    ///
+    /// # Safety
+    ///
+    /// `len`不能大于`SIZE`，并且`buf[..len]`必须是有效的 UTF-8。
+    #[inline(always)]
+    pub const unsafe fn from_buf_and_len_unchecked(
+        buf: [u8; SIZE],
+        len: usize,
+    ) -> SmallString<SIZE> {
+        SmallString {
+            data: SmallVec::from_buf_and_len_unchecked(MaybeUninit::new(buf), len),
+        }
+    }
+
     /// 该字符串可以内联容纳的最大字节数。
     #[inline(always)]
     pub fn inline_size(&self) -> usize {
@@ -135,20 +375,32 @@ impl<const SIZE: usize> SmallString<SIZE> {
         self.data.spilled()
     }
 
-    /// 清空字符串并返回其先前内容的迭代器。
-    pub fn drain(&mut self) -> Drain {
-        unsafe {
-            let len = self.len();
-
-            self.data.set_len(0);
-
-            let ptr = self.as_ptr();
-
-            let slice = slice::from_raw_parts(ptr, len);
-            let s = str::from_utf8_unchecked(slice);
-
-            Drain { iter: s.chars() }
-        }
+    /// 从字符串中移除指定的字节范围，并以迭代器的形式返回被移除的字符。
+    ///
+    /// 与`String::drain`一致：迭代器被丢弃时才移除该范围并将其后的内容前移，
+    /// 即使迭代器没有被消耗完。如果迭代器被泄漏（例如通过`mem::forget`），字符串保持不变。
+    ///
+    /// # Panics
+    ///
+    /// 如果起点大于终点、终点超过字符串长度，或者任一端不在`char`边界上。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<16> = SmallString::from("α is alpha");
+    ///
+    /// let alpha: String = s.drain(..2).collect();
+    ///
+    /// assert_eq!(alpha, "α");
+    /// assert_eq!(s, " is alpha");
+    ///
+    /// s.drain(..);
+    /// assert!(s.is_empty());
+    /// ```
+    pub fn drain<R: ops::RangeBounds<usize>>(&mut self, range: R) -> Drain<'_> {
+        Drain::new(self, range)
     }
 
     /// 将给定的`char`附加到该字符串的末尾。
@@ -172,7 +424,10 @@ impl<const SIZE: usize> SmallString<SIZE> {
         }
     }
 
-    /// 将给定的数字附加到该字符串的末尾。
+    /// 将给定的整数附加到该字符串的末尾。
+    ///
+    /// 支持所有内置整数类型（`i8`..`i128`、`u8`..`u128`、`isize`、`usize`），
+    /// 负数会带上`-`前缀。
     ///
     /// # Examples
     ///
@@ -184,46 +439,231 @@ impl<const SIZE: usize> SmallString<SIZE> {
     /// s.push_integer(12345);
     ///
     /// assert_eq!(s, "foo12345");
+    ///
+    /// let mut s: SmallString<8> = SmallString::new();
+    ///
+    /// s.push_integer(-42_i32);
+    ///
+    /// assert_eq!(s, "-42");
+    /// ```
+    #[inline(always)]
+    pub fn push_integer<T: SmallInteger>(&mut self, num: T) {
+        num.push_to(self);
+    }
+
+    push_unsigned!(push_u64, u64, integer::count_digits_u64);
+    push_unsigned!(push_u128, u128, integer::count_digits_u128);
+    push_radix!(push_radix_u64, u64);
+    push_radix!(push_radix_u128, u128);
+
+    /// 将给定的整数以小写十六进制附加到该字符串的末尾。
+    ///
+    /// 与`format!("{:x}", num)`一致，有符号整数按二进制补码输出。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<8> = SmallString::from("0x");
+    ///
+    /// s.push_hex(0xbeef_u32);
+    ///
+    /// assert_eq!(s, "0xbeef");
+    /// ```
+    #[inline(always)]
+    pub fn push_hex<T: SmallInteger>(&mut self, num: T) {
+        num.push_radix_to(self, 4, integer::DIGITS_LOWER);
+    }
+
+    /// 将给定的整数以大写十六进制附加到该字符串的末尾。
+    ///
+    /// 与`format!("{:X}", num)`一致，有符号整数按二进制补码输出。
+    #[inline(always)]
+    pub fn push_hex_upper<T: SmallInteger>(&mut self, num: T) {
+        num.push_radix_to(self, 4, integer::DIGITS_UPPER);
+    }
+
+    /// 将给定的整数以八进制附加到该字符串的末尾。
+    ///
+    /// 与`format!("{:o}", num)`一致，有符号整数按二进制补码输出。
+    #[inline(always)]
+    pub fn push_octal<T: SmallInteger>(&mut self, num: T) {
+        num.push_radix_to(self, 3, integer::DIGITS_LOWER);
+    }
+
+    /// 将给定的整数以二进制附加到该字符串的末尾。
+    ///
+    /// 与`format!("{:b}", num)`一致，有符号整数按二进制补码输出。
+    #[inline(always)]
+    pub fn push_binary<T: SmallInteger>(&mut self, num: T) {
+        num.push_radix_to(self, 1, integer::DIGITS_LOWER);
+    }
+
+    /// 将给定的整数以十进制附加到该字符串的末尾，不足`width`个字符时在左侧用`fill`补齐。
+    ///
+    /// 当`fill`为`'0'`时，补齐的`0`位于负号之后，与`format!("{:0width$}", num)`一致；
+    /// 否则与`format!("{:fill>width$}", num)`一致。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<16> = SmallString::new();
+    ///
+    /// s.push_integer_padded(-42, 5, '0');
+    /// s.push_integer_padded(7_u8, 3, ' ');
+    ///
+    /// assert_eq!(s, "-0042  7");
+    /// ```
+    #[inline(always)]
+    pub fn push_integer_padded<T: SmallInteger>(&mut self, num: T, width: usize, fill: char) {
+        let start = self.len();
+        self.push_integer(num);
+        self.pad_start(start, width, fill);
+    }
+
+    /// 同 [`push_hex`](#method.push_hex)，不足`width`个字符时在左侧用`fill`补齐。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<16> = SmallString::new();
+    ///
+    /// s.push_hex_padded(0xff_u32, 8, '0');
+    ///
+    /// assert_eq!(s, "000000ff");
     /// ```
     #[inline(always)]
-    pub fn push_integer(&mut self, mut num: u64) {
-        let num_len = if num < 10 {
-            1
+    pub fn push_hex_padded<T: SmallInteger>(&mut self, num: T, width: usize, fill: char) {
+        let start = self.len();
+        self.push_hex(num);
+        self.pad_start(start, width, fill);
+    }
+
+    /// 同 [`push_hex_upper`](#method.push_hex_upper)，不足`width`个字符时在左侧用`fill`补齐。
+    #[inline(always)]
+    pub fn push_hex_upper_padded<T: SmallInteger>(&mut self, num: T, width: usize, fill: char) {
+        let start = self.len();
+        self.push_hex_upper(num);
+        self.pad_start(start, width, fill);
+    }
+
+    /// 同 [`push_octal`](#method.push_octal)，不足`width`个字符时在左侧用`fill`补齐。
+    #[inline(always)]
+    pub fn push_octal_padded<T: SmallInteger>(&mut self, num: T, width: usize, fill: char) {
+        let start = self.len();
+        self.push_octal(num);
+        self.pad_start(start, width, fill);
+    }
+
+    /// 同 [`push_binary`](#method.push_binary)，不足`width`个字符时在左侧用`fill`补齐。
+    #[inline(always)]
+    pub fn push_binary_padded<T: SmallInteger>(&mut self, num: T, width: usize, fill: char) {
+        let start = self.len();
+        self.push_binary(num);
+        self.pad_start(start, width, fill);
+    }
+
+    /// 在`start`处插入`fill`，使`start`之后刚写入的ASCII数字至少占`width`个字符。
+    ///
+    /// `fill`为`'0'`时跳过开头的负号。
+    pub(crate) fn pad_start(&mut self, start: usize, width: usize, fill: char) {
+        let len = self.len();
+        let written = len - start;
+        if written >= width {
+            return;
+        }
+
+        let mut buf = [0; 4];
+        let fill = fill.encode_utf8(&mut buf).as_bytes();
+        let pad = width - written;
+        let amt = pad * fill.len();
+
+        let index = if fill == b"0" && self.data[start] == b'-' {
+            start + 1
         } else {
-            // 我们计算出num的个位数数量
-            unsafe { unchecked_add((num as f64).log10() as usize, 1) }
+            start
         };
 
-        let len = self.data.len();
-        if len + num_len > self.data.capacity() {
-            // 如果容量不够我们需要增长容量
-            self.data.grow(self.data.len() * 2);
-        }
+        self.reserve(amt);
 
-        // 如果是num是奇数我们先处理最后一位
-        if num_len & 1 == 1 {
-            unsafe {
-                self.data
-                    .as_mut_ptr()
-                    .add(unchecked_sub(unchecked_add(len, num_len), 1))
-                    .write(unchecked_add(b'0', (num % 10_u64) as u8));
-                num = unchecked_div(num, 10);
+        unsafe {
+            let ptr = self.data.as_mut_ptr();
+            ptr::copy(ptr.add(index), ptr.add(index + amt), len - index);
+            for i in 0..pad {
+                ptr::copy_nonoverlapping(
+                    fill.as_ptr(),
+                    ptr.add(index + i * fill.len()),
+                    fill.len(),
+                );
             }
+            self.data.set_len(len + amt);
         }
+    }
 
-        for i in (0..(num_len & !1) >> 1).rev() {
-            unsafe {
-                let pos = unchecked_add(len, unchecked_mul(i, 2));
-                let digit_pos = (unchecked_rem(num, 100) << 1) as usize;
+    /// 将给定的`f64`以能往返转换回原值的最短十进制表示附加到该字符串的末尾。
+    ///
+    /// 输出与`format!("{}", num)`完全一致：不使用科学计数法，`NaN`输出为`NaN`，
+    /// 无穷大输出为`inf`或`-inf`，负零输出为`-0`。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<16> = SmallString::from("x=");
+    ///
+    /// s.push_f64(0.1 + 0.2);
+    ///
+    /// assert_eq!(s, "x=0.30000000000000004");
+    /// ```
+    #[inline(always)]
+    pub fn push_f64(&mut self, num: f64) {
+        let (negative, decoded) = float::F64.decode(num.to_bits(), 11);
+        float::push_shortest(self, &float::F64, negative, decoded);
+    }
 
-                // 我们每次处理16bit在大多数平台多16bit处理效率更高
-                (self.data.as_mut_ptr().add(pos) as *mut u16)
-                    .write((Self::DIGIT_PAIRS.as_ptr().add(digit_pos) as *const u16).read());
-                num = unchecked_div(num, 100);
-            }
-        }
-        // 最后我们需要调整数组的真实长度
-        unsafe { self.data.set_len(len + num_len) }
+    /// 将给定的`f32`以能往返转换回原值的最短十进制表示附加到该字符串的末尾。
+    ///
+    /// 输出与`format!("{}", num)`完全一致，参见 [`push_f64`](#method.push_f64)。
+    #[inline(always)]
+    pub fn push_f32(&mut self, num: f32) {
+        let (negative, decoded) = float::F32.decode(num.to_bits() as u64, 8);
+        float::push_shortest(self, &float::F32, negative, decoded);
+    }
+
+    /// 将给定的`f64`保留`precision`位小数附加到该字符串的末尾。
+    ///
+    /// 输出与`format!("{:.precision$}", num)`完全一致：按精确值舍入，恰好在中间时向偶数舍入。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<16> = SmallString::new();
+    ///
+    /// s.push_f64_fixed(3.14159, 3);
+    ///
+    /// assert_eq!(s, "3.142");
+    /// ```
+    #[inline(always)]
+    pub fn push_f64_fixed(&mut self, num: f64, precision: usize) {
+        let (negative, decoded) = float::F64.decode(num.to_bits(), 11);
+        float::push_fixed(self, &float::F64, negative, decoded, precision);
+    }
+
+    /// 将给定的`f32`保留`precision`位小数附加到该字符串的末尾。
+    ///
+    /// 输出与`format!("{:.precision$}", num)`完全一致，参见 [`push_f64_fixed`](#method.push_f64_fixed)。
+    #[inline(always)]
+    pub fn push_f32_fixed(&mut self, num: f32, precision: usize) {
+        let (negative, decoded) = float::F32.decode(num.to_bits() as u64, 8);
+        float::push_fixed(self, &float::F32, negative, decoded, precision);
     }
 
     /// 将给定的字符串切片附加到该字符串的末尾。
@@ -241,6 +681,7 @@ impl<const SIZE: usize> SmallString<SIZE> {
     /// ```
     #[inline(always)]
     pub fn push_str(&mut self, s: &str) {
+        self.reserve(s.len());
         self.data.extend_from_slice(s.as_bytes());
     }
 
@@ -266,21 +707,104 @@ impl<const SIZE: usize> SmallString<SIZE> {
     /// 如果`new_cap`小于当前长度。
     #[inline(always)]
     pub fn grow(&mut self, new_cap: usize) {
+        self.release_empty_heap();
         self.data.grow(new_cap);
     }
 
+    /// 重新分配以将新容量设置为`new_cap`。
+    ///
+    /// 如果分配内存失败，则返回错误，字符串保持不变。
+    ///
+    /// # Panics
+    ///
+    /// 如果`new_cap`小于当前长度。
+    #[inline(always)]
+    pub fn try_grow(&mut self, new_cap: usize) -> Result<(), TryReserveError> {
+        self.release_empty_heap();
+        self.data.try_grow(new_cap).map_err(TryReserveError::from)
+    }
+
     /// 确保该字符串的容量至少比其长度大`additional`字节。
     ///
     /// 为了防止频繁的重新分配，容量可以增加超过`additional`字节。
     #[inline(always)]
     pub fn reserve(&mut self, additional: usize) {
+        if additional > self.data.capacity() {
+            self.release_empty_heap();
+        }
         self.data.reserve(additional);
     }
 
     /// 确保该字符串的容量比其长度大`additional`字节。
+    ///
+    /// 与 [`reserve`](#method.reserve) 不同，该方法不会为了减少重新分配而额外分配容量。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<4> = SmallString::from("foo");
+    ///
+    /// s.reserve_exact(10);
+    ///
+    /// assert_eq!(s.capacity(), 13);
+    /// ```
     #[inline(always)]
     pub fn reserve_exact(&mut self, additional: usize) {
-        self.data.reserve(additional);
+        if additional > self.data.capacity() {
+            self.release_empty_heap();
+        }
+        self.data.reserve_exact(additional);
+    }
+
+    /// 确保该字符串的容量至少比其长度大`additional`字节。
+    ///
+    /// 如果容量溢出或分配内存失败，则返回错误，字符串保持不变。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::{SmallString, TryReserveError};
+    ///
+    /// let mut s: SmallString<8> = SmallString::from("foo");
+    ///
+    /// assert!(s.try_reserve(100).is_ok());
+    /// assert_eq!(s.try_reserve(usize::MAX), Err(TryReserveError::CapacityOverflow));
+    /// assert_eq!(s, "foo");
+    /// ```
+    #[inline(always)]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if additional > self.data.capacity() {
+            self.release_empty_heap();
+        }
+        self.data
+            .try_reserve(additional)
+            .map_err(TryReserveError::from)
+    }
+
+    /// 确保该字符串的容量比其长度大`additional`字节。
+    ///
+    /// 如果容量溢出或分配内存失败，则返回错误，字符串保持不变。
+    #[inline(always)]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if additional > self.data.capacity() {
+            self.release_empty_heap();
+        }
+        self.data
+            .try_reserve_exact(additional)
+            .map_err(TryReserveError::from)
+    }
+
+    /// 如果字符串为空却仍持有堆缓冲区，释放该缓冲区并回到内联存储。
+    ///
+    /// `smallvec`在长度为0时调整堆缓冲区的大小会直接分配新的缓冲区而不释放旧的，
+    /// 所有可能调整堆缓冲区大小的路径都要先调用该方法。
+    #[inline(always)]
+    fn release_empty_heap(&mut self) {
+        if self.data.is_empty() && self.data.spilled() {
+            self.data.shrink_to_fit();
+        }
     }
 
     /// 尽可能缩小字符串的容量。
@@ -291,6 +815,39 @@ impl<const SIZE: usize> SmallString<SIZE> {
         self.data.shrink_to_fit();
     }
 
+    /// 将字符串的容量缩小到不小于`min_capacity`和当前长度中的较大者。
+    ///
+    /// 如果该值不超过 [`inline_size`](#method.inline_size)，数据会从堆缓冲区移回内联存储。
+    /// 如果当前容量已经小于`min_capacity`，则不做任何事。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<4> = SmallString::with_capacity(100);
+    /// s.push_str("foo");
+    ///
+    /// s.shrink_to(10);
+    /// assert_eq!(s.capacity(), 10);
+    ///
+    /// s.shrink_to(0);
+    /// assert!(!s.spilled());
+    /// assert_eq!(s, "foo");
+    /// ```
+    #[inline(always)]
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        if self.data.is_empty() && self.data.spilled() && min_capacity < self.data.capacity() {
+            self.release_empty_heap();
+            if min_capacity > self.inline_size() {
+                self.data.grow(min_capacity);
+            }
+            return;
+        }
+
+        self.data.shrink_to(min_capacity);
+    }
+
     /// 缩短字符串，保留前一个`len`字节。
     ///
     /// 这不会重新分配。如果要缩小字符串的容量，请在截断后使用`shrink_to_fit`。
@@ -306,13 +863,80 @@ impl<const SIZE: usize> SmallString<SIZE> {
     /// 提取包含整个字符串的字符串切片。
     #[inline(always)]
     pub fn as_str(&self) -> &str {
-        unsafe { str::from_raw_parts(self.data.as_ptr(), self.data.len()) }
+        #[cfg(feature = "nightly")]
+        unsafe {
+            str::from_raw_parts(self.data.as_ptr(), self.data.len())
+        }
+        #[cfg(not(feature = "nightly"))]
+        unsafe {
+            str::from_utf8_unchecked(&self.data)
+        }
+    }
+
+    /// 比较两个内联容量可能不同的字符串的内容是否相等。
+    ///
+    /// `PartialEq`只在相同的`SIZE`之间实现，这样`s == SmallString::from("x")`
+    /// 这类表达式仍然可以推断出右侧的类型；不同`SIZE`之间的比较使用该方法。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let a: SmallString<4> = SmallString::from("foo");
+    /// let b: SmallString<32> = SmallString::from("foo");
+    ///
+    /// assert!(a.eq_any_size(&b));
+    /// ```
+    #[inline(always)]
+    pub fn eq_any_size<const B: usize>(&self, other: &SmallString<B>) -> bool {
+        self[..] == other[..]
+    }
+
+    /// 按字典序比较两个内联容量可能不同的字符串，结果与比较对应的`str`一致。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::cmp::Ordering;
+    /// use smallstr::SmallString;
+    ///
+    /// let a: SmallString<4> = SmallString::from("bar");
+    /// let b: SmallString<32> = SmallString::from("foo");
+    ///
+    /// assert_eq!(a.cmp_any_size(&b), Ordering::Less);
+    /// ```
+    #[inline(always)]
+    pub fn cmp_any_size<const B: usize>(&self, other: &SmallString<B>) -> Ordering {
+        self[..].cmp(&other[..])
     }
 
     /// 提取包含整个字符串的字符串切片。
     #[inline(always)]
     pub fn as_mut_str(&mut self) -> &mut str {
-        unsafe { str::from_raw_parts_mut(self.data.as_mut_ptr(), self.data.len()) }
+        #[cfg(feature = "nightly")]
+        unsafe {
+            str::from_raw_parts_mut(self.data.as_mut_ptr(), self.data.len())
+        }
+        #[cfg(not(feature = "nightly"))]
+        unsafe {
+            str::from_utf8_unchecked_mut(&mut self.data)
+        }
+    }
+
+    /// 返回字符串的子切片，不会因越界或不在`char`边界上而崩溃。
+    ///
+    /// 接受所有可以用来索引`str`的类型，包括`a..=b`、`..=b`和`(Bound, Bound)`，
+    /// 如果索引无效则返回`None`。
+    #[inline(always)]
+    pub fn get<I: SliceIndex<str>>(&self, index: I) -> Option<&I::Output> {
+        self.as_str().get(index)
+    }
+
+    /// 返回字符串的可变子切片，如果索引无效则返回`None`。
+    #[inline(always)]
+    pub fn get_mut<I: SliceIndex<str>>(&mut self, index: I) -> Option<&mut I::Output> {
+        self.as_mut_str().get_mut(index)
     }
 
     /// 删除字符串的所有内容。
@@ -337,11 +961,8 @@ impl<const SIZE: usize> SmallString<SIZE> {
         let len = self.len();
 
         unsafe {
-            ptr::copy(
-                self.as_ptr().add(next),
-                self.as_mut_ptr().add(index),
-                len - next,
-            );
+            let ptr = self.data.as_mut_ptr();
+            ptr::copy(ptr.add(next), ptr.add(index), len - next);
             self.data.set_len(len - ch_len);
         }
 
@@ -373,19 +994,444 @@ impl<const SIZE: usize> SmallString<SIZE> {
         let len = self.len();
         let amt = s.len();
 
-        self.data.reserve(amt);
+        self.reserve(amt);
 
         unsafe {
-            ptr::copy(
-                self.as_ptr().add(index),
-                self.as_mut_ptr().add(index + amt),
-                len - index,
-            );
-            ptr::copy_nonoverlapping(s.as_ptr(), self.as_mut_ptr().add(index), amt);
+            let ptr = self.data.as_mut_ptr();
+            ptr::copy(ptr.add(index), ptr.add(index + amt), len - index);
+            ptr::copy_nonoverlapping(s.as_ptr(), ptr.add(index), amt);
             self.data.set_len(len + amt);
         }
     }
 
+    /// 用`replace_with`替换字符串中指定的字节范围。
+    ///
+    /// 范围之后的内容只移动一次；只有替换后变长且容量不足时才会重新分配。
+    ///
+    /// # Panics
+    ///
+    /// 如果起点大于终点、终点超过字符串长度，或者任一端不在`char`边界上。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<16> = SmallString::from("α is alpha");
+    ///
+    /// s.replace_range(..2, "β");
+    /// assert_eq!(s, "β is alpha");
+    ///
+    /// s.replace_range(6.., "beta");
+    /// assert_eq!(s, "β is beta");
+    /// ```
+    pub fn replace_range<R: ops::RangeBounds<usize>>(&mut self, range: R, replace_with: &str) {
+        let len = self.len();
+        let (start, end) = slice_range(range, len);
+        assert!(self.is_char_boundary(start));
+        assert!(self.is_char_boundary(end));
+
+        let amt = replace_with.len();
+        if amt > end - start {
+            self.reserve(amt - (end - start));
+        }
+
+        unsafe {
+            let ptr = self.data.as_mut_ptr();
+            ptr::copy(ptr.add(end), ptr.add(start + amt), len - end);
+            ptr::copy_nonoverlapping(replace_with.as_ptr(), ptr.add(start), amt);
+            self.data.set_len(len - (end - start) + amt);
+        }
+    }
+
+    /// 在给定的字节位置将字符串一分为二，返回`[at, len)`部分。
+    ///
+    /// 该字符串保留`[0, at)`部分和原有的容量；返回的字符串如果足够短则存储在内联缓冲区中。
+    ///
+    /// # Panics
+    ///
+    /// 如果`at`超过字符串长度或者不在`char`边界上。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<8> = SmallString::from("foobar");
+    ///
+    /// let bar = s.split_off(3);
+    ///
+    /// assert_eq!(s, "foo");
+    /// assert_eq!(bar, "bar");
+    /// ```
+    #[inline(always)]
+    #[must_use = "use `.truncate()` if you don't need the other half"]
+    pub fn split_off(&mut self, at: usize) -> SmallString<SIZE> {
+        assert!(self.is_char_boundary(at));
+
+        let other = SmallString::from_str(&self[at..]);
+        unsafe { self.data.set_len(at) };
+        other
+    }
+
+    /// 在给定的字节位置将字符串一分为二，返回`([0, at), [at, len))`。
+    ///
+    /// 前一半沿用原有的缓冲区，参见 [`split_off`](#method.split_off)。
+    ///
+    /// # Panics
+    ///
+    /// 如果`at`超过字符串长度或者不在`char`边界上。
+    #[inline(always)]
+    pub fn split_at_owned(mut self, at: usize) -> (SmallString<SIZE>, SmallString<SIZE>) {
+        let other = self.split_off(at);
+        (self, other)
+    }
+
+    /// 就地移除开头和结尾的空白字符。
+    ///
+    /// 与`str::trim`一致，只移动一次剩余的内容，不会重新分配。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<16> = SmallString::from("\t foo bar \n");
+    ///
+    /// s.trim_in_place();
+    ///
+    /// assert_eq!(s, "foo bar");
+    /// ```
+    #[inline(always)]
+    pub fn trim_in_place(&mut self) {
+        self.trim_end_in_place();
+        self.trim_start_in_place();
+    }
+
+    /// 就地移除开头的空白字符，参见`str::trim_start`。
+    #[inline(always)]
+    pub fn trim_start_in_place(&mut self) {
+        let amt = self.len() - self.trim_start().len();
+        self.remove_prefix(amt);
+    }
+
+    /// 就地移除结尾的空白字符，参见`str::trim_end`。
+    #[inline(always)]
+    pub fn trim_end_in_place(&mut self) {
+        let len = self.trim_end().len();
+        self.data.truncate(len);
+    }
+
+    /// 如果字符串以`prefix`开头，则就地移除该前缀并返回`true`，否则返回`false`。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<16> = SmallString::from("Bearer token");
+    ///
+    /// assert!(s.strip_prefix_in_place("Bearer "));
+    /// assert!(!s.strip_prefix_in_place("Bearer "));
+    ///
+    /// assert_eq!(s, "token");
+    /// ```
+    #[inline(always)]
+    pub fn strip_prefix_in_place<P: SmallPattern>(&mut self, mut prefix: P) -> bool {
+        match prefix.prefix_len_in(self) {
+            Some(amt) => {
+                // `SmallPattern`可以在外部实现，不能信任返回的长度
+                assert!(
+                    self.is_char_boundary(amt),
+                    "SmallPattern::prefix_len_in returned an invalid length"
+                );
+                self.remove_prefix(amt);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 如果字符串以`suffix`结尾，则就地移除该后缀并返回`true`，否则返回`false`。
+    #[inline(always)]
+    pub fn strip_suffix_in_place<P: SmallPattern>(&mut self, mut suffix: P) -> bool {
+        match suffix.suffix_len_in(self) {
+            Some(amt) => {
+                let len = self.len().checked_sub(amt);
+                // `SmallPattern`可以在外部实现，不能信任返回的长度
+                let len = match len {
+                    Some(len) if self.is_char_boundary(len) => len,
+                    _ => panic!("SmallPattern::suffix_len_in returned an invalid length"),
+                };
+                self.data.truncate(len);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 移除开头的`amt`个字节。
+    ///
+    /// # Panics
+    ///
+    /// 如果`amt`超过字符串长度或者不在`char`边界上。
+    #[inline(always)]
+    fn remove_prefix(&mut self, amt: usize) {
+        assert!(self.is_char_boundary(amt));
+        if amt == 0 {
+            return;
+        }
+
+        let len = self.len();
+        unsafe {
+            let ptr = self.data.as_mut_ptr();
+            ptr::copy(ptr.add(amt), ptr, len - amt);
+            self.data.set_len(len - amt);
+        }
+    }
+
+    /// 将所有匹配`from`的部分替换为`to`，返回新的`SmallString`。
+    ///
+    /// 与`str::replace`一致，但结果在足够短时存储在内联缓冲区中，而不是分配`String`。
+    /// `from`可以是`char`、`&str`或`FnMut(char) -> bool`闭包等，参见 [`SmallPattern`]。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let s: SmallString<16> = SmallString::from("a-b_c-d");
+    ///
+    /// assert_eq!(s.replace('-', "+"), "a+b_c+d");
+    /// assert_eq!(s.replace("-b", ""), "a_c-d");
+    /// assert_eq!(s.replace(|ch| ch == '-' || ch == '_', " "), "a b c d");
+    /// ```
+    #[inline(always)]
+    pub fn replace<P: SmallPattern>(&self, from: P, to: &str) -> SmallString<SIZE> {
+        self.replacen(from, to, usize::MAX)
+    }
+
+    /// 将前`count`个匹配`from`的部分替换为`to`，返回新的`SmallString`。
+    ///
+    /// 与`str::replacen`一致，参见 [`replace`](#method.replace)。
+    pub fn replacen<P: SmallPattern>(
+        &self,
+        mut from: P,
+        to: &str,
+        count: usize,
+    ) -> SmallString<SIZE> {
+        let mut result = SmallString::new();
+        let mut last = 0;
+        let mut pos = 0;
+
+        for _ in 0..count {
+            let (start, end) = match from.find_in(&self[pos..]) {
+                Some((start, end)) => (pos + start, pos + end),
+                None => break,
+            };
+
+            result.push_str(&self[last..start]);
+            result.push_str(to);
+            last = end;
+            pos = end;
+
+            // 空匹配之后跳过一个字符，否则会在同一位置反复匹配
+            if start == end {
+                match self[end..].chars().next() {
+                    Some(ch) => pos += ch.len_utf8(),
+                    None => break,
+                }
+            }
+        }
+
+        result.push_str(&self[last..]);
+        result
+    }
+
+    /// 将所有匹配`from`的部分就地替换为`to`。
+    ///
+    /// 如果`to`不长于任何可能的匹配（例如等长或更短的`&str`），则在原缓冲区中一次完成，
+    /// 不会分配内存；否则等价于`*self = self.replace(from, to)`。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<16> = SmallString::from("a, b, c");
+    ///
+    /// s.replace_in_place(", ", ";");
+    ///
+    /// assert_eq!(s, "a;b;c");
+    /// ```
+    pub fn replace_in_place<P: SmallPattern>(&mut self, mut from: P, to: &str) {
+        if to.len() > from.min_len() {
+            *self = self.replace(from, to);
+            return;
+        }
+
+        /// 将`read`之后尚未处理的内容移到`write`处并提交长度，模式中的闭包发生`panic`时同样如此，
+        /// 因此字符串始终是有效的 UTF-8。
+        struct CompactOnDrop<'a, const SIZE: usize> {
+            s: &'a mut SmallString<SIZE>,
+            len: usize,
+            read: usize,
+            write: usize,
+        }
+
+        impl<const SIZE: usize> Drop for CompactOnDrop<'_, SIZE> {
+            fn drop(&mut self) {
+                unsafe {
+                    let ptr = self.s.data.as_mut_ptr();
+                    ptr::copy(
+                        ptr.add(self.read),
+                        ptr.add(self.write),
+                        self.len - self.read,
+                    );
+                    self.s.data.set_len(self.write + self.len - self.read);
+                }
+            }
+        }
+
+        let len = self.len();
+        let mut guard = CompactOnDrop::<SIZE> {
+            s: self,
+            len,
+            read: 0,
+            write: 0,
+        };
+        let ptr = guard.s.data.as_mut_ptr();
+
+        // `write`始终不超过`read`，`read`之后的内容保持原样，因此每次只在其上查找；
+        // `write`只在写完一段完整的 UTF-8 之后才更新
+        unsafe {
+            loop {
+                let read = guard.read;
+                let rest =
+                    str::from_utf8_unchecked(slice::from_raw_parts(ptr.add(read), len - read));
+                let (start, end) = match from.find_in(rest) {
+                    Some(range) => range,
+                    None => break,
+                };
+                // `SmallPattern`可以在外部实现，下面的指针操作不能信任其返回值
+                assert!(
+                    start <= end && rest.is_char_boundary(start) && rest.is_char_boundary(end),
+                    "SmallPattern::find_in returned an invalid range"
+                );
+                assert!(
+                    to.len() <= end - start,
+                    "SmallPattern::find_in returned a match shorter than min_len"
+                );
+                let (start, end) = (read + start, read + end);
+
+                let mut write = guard.write;
+                ptr::copy(ptr.add(read), ptr.add(write), start - read);
+                write += start - read;
+                ptr::copy_nonoverlapping(to.as_ptr(), ptr.add(write), to.len());
+                write += to.len();
+                guard.write = write;
+                guard.read = end;
+
+                if start == end {
+                    let next =
+                        str::from_utf8_unchecked(slice::from_raw_parts(ptr.add(end), len - end));
+                    let ch_len = match next.chars().next() {
+                        Some(ch) => ch.len_utf8(),
+                        None => break,
+                    };
+                    ptr::copy(ptr.add(end), ptr.add(write), ch_len);
+                    guard.write += ch_len;
+                    guard.read += ch_len;
+                }
+            }
+        }
+
+        drop(guard);
+    }
+
+    /// 将给定的`char`附加到该字符串的末尾。
+    ///
+    /// 与 [`push`](#method.push) 不同，如果分配内存失败，该方法会返回错误而不是中止程序。
+    /// 需要在内联容量用尽时返回错误请使用 [`InlineString`]。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<2> = SmallString::new();
+    ///
+    /// s.try_push('a')?;
+    /// s.try_push('α')?;
+    ///
+    /// assert_eq!(s, "aα");
+    /// # Ok::<(), smallstr::TryReserveError>(())
+    /// ```
+    ///
+    /// [`InlineString`]: struct.InlineString.html
+    #[inline(always)]
+    pub fn try_push(&mut self, ch: char) -> Result<(), TryReserveError> {
+        self.try_push_str(ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// 将给定的字符串切片附加到该字符串的末尾。
+    ///
+    /// 如果分配内存失败，则返回错误，字符串保持不变。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<4> = SmallString::from("foo");
+    ///
+    /// assert_eq!(s.try_push_str("bar"), Ok(()));
+    /// assert_eq!(s, "foobar");
+    /// ```
+    #[inline(always)]
+    pub fn try_push_str(&mut self, s: &str) -> Result<(), TryReserveError> {
+        self.try_reserve(s.len())?;
+        self.push_str(s);
+        Ok(())
+    }
+
+    /// 将`char`插入到该字符串的给定字节位置。
+    ///
+    /// 如果分配内存失败，则返回错误，字符串保持不变。
+    /// # Panics
+    ///
+    /// 如果`index`不在`char`边界上。
+    #[inline(always)]
+    pub fn try_insert(&mut self, index: usize, ch: char) -> Result<(), TryReserveError> {
+        self.try_insert_str(index, ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// 将 `&str` 插入到该字符串的给定字节位置。
+    ///
+    /// 如果分配内存失败，则返回错误，字符串保持不变。
+    /// # Panics
+    ///
+    /// 如果`index`不在`char`边界上。
+    #[inline(always)]
+    pub fn try_insert_str(&mut self, index: usize, s: &str) -> Result<(), TryReserveError> {
+        assert!(self.is_char_boundary(index));
+
+        self.try_reserve(s.len())?;
+        self.insert_str(index, s);
+        Ok(())
+    }
+
+    /// 将给定的整数以十进制附加到该字符串的末尾。
+    ///
+    /// 如果分配内存失败，则返回错误，字符串保持不变。
+    #[inline(always)]
+    pub fn try_push_integer<T: SmallInteger>(&mut self, num: T) -> Result<(), TryReserveError> {
+        // 任何整数的十进制表示都不超过40个字节，先在栈上格式化
+        let mut buf = SmallString::<40>::new();
+        buf.push_integer(num);
+        self.try_push_str(&buf)
+    }
+
     /// 返回对`SmallString`内容的可变引用。
     ///
     /// # Safety
@@ -394,9 +1440,18 @@ impl<const SIZE: usize> SmallString<SIZE> {
     /// 如果违反此约束，可能会导致内存不安全问题，因为 Rust 标准库函数假定 `&str` 是有效的 UTF-8。
     #[inline(always)]
     pub unsafe fn as_mut_vec(&mut self) -> &mut SmallVec<u8, SIZE> {
+        self.release_empty_heap();
         &mut self.data
     }
 
+    /// 将字符串编码为 UTF-16，结果直接写入`SmallVec<u16, N>`。
+    ///
+    /// 在`N`足够容纳所有代码单元时不会产生堆分配。
+    #[inline(always)]
+    pub fn to_utf16_small<const N: usize>(&self) -> SmallVec<u16, N> {
+        self.encode_utf16().collect()
+    }
+
     /// 如果`SmallString`已经溢出到堆上、在转换不成中不会重新分配内存。而是使用`Vec::from_raw_parts`构造Vec
     /// 如果`SmallString`没有溢出到堆上、会创建Vec并将栈上数据拷贝至新创建的Vec
     #[inline(always)]
@@ -406,10 +1461,43 @@ impl<const SIZE: usize> SmallString<SIZE> {
 
     /// 将 `SmallString`转换为`Box<string>`，如果 `SmallString` 已经溢出到堆上，则不进行分配。
     ///
-    /// 请注意，这将减少过剩产能。
+    /// 请注意，这将减少过剩产能。
+    #[inline(always)]
+    pub fn into_boxed_str(self) -> Box<str> {
+        self.into_string().into_boxed_str()
+    }
+
+    /// 将`SmallString<SIZE>`转换为内联容量不同的`SmallString<NEW>`。
+    ///
+    /// 如果数据已经溢出到堆上，则直接转移堆缓冲区而不复制；否则只复制已使用的内联字节，
+    /// 超出`NEW`时新字符串会分配堆内存。
+    ///
+    /// 泛型的`From<SmallString<A>> for SmallString<B>`会与标准库的`impl<T> From<T> for T`冲突，
+    /// 因此只能通过该方法在不同大小之间转换。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let s: SmallString<16> = SmallString::from("a string that has spilled");
+    /// let ptr = s.as_ptr();
+    ///
+    /// let s: SmallString<64> = s.resize_inline();
+    ///
+    /// assert!(s.spilled());
+    /// assert_eq!(s.as_ptr(), ptr);
+    /// assert_eq!(s, "a string that has spilled");
+    /// ```
     #[inline(always)]
-    pub fn into_boxed_str(self) -> Box<str> {
-        self.into_string().into_boxed_str()
+    pub fn resize_inline<const NEW: usize>(self) -> SmallString<NEW> {
+        if self.spilled() {
+            SmallString {
+                data: SmallVec::from_vec(self.data.into_vec()),
+            }
+        } else {
+            SmallString::from_str(&self)
+        }
     }
 
     /// 如果可能的话，将 `SmallString` 转换为 `[u8; SIZE]`。否则，返回`Err(Self)`。
@@ -420,6 +1508,25 @@ impl<const SIZE: usize> SmallString<SIZE> {
         self.data.into_inner().map_err(|data| SmallString { data })
     }
 
+    /// 如果可能的话，将 `SmallString` 转换为字节数组和有效内容的长度，是 [`from_buf_and_len`] 的逆操作。
+    ///
+    /// 与 [`into_inner`] 不同，字符串不需要正好填满`SIZE`个字节，数组中`len`之后的字节为零。
+    /// 只要长度不超过`SIZE`就会成功，即使内容已经溢出到堆中；否则返回`Err(self)`。
+    ///
+    /// [`from_buf_and_len`]: #method.from_buf_and_len
+    /// [`into_inner`]: #method.into_inner
+    #[inline(always)]
+    pub fn into_inline_parts(self) -> Result<([u8; SIZE], usize), SmallString<SIZE>> {
+        let len = self.len();
+        if len > SIZE {
+            return Err(self);
+        }
+
+        let mut buf = [0; SIZE];
+        buf[..len].copy_from_slice(self.as_bytes());
+        Ok((buf, len))
+    }
+
     /// 仅保留谓词指定的字符。
     ///
     /// 换句话说，删除所有字符`c`，以便`f(c)`返回`false`。此方法就地运行并保留保留字符的顺序。
@@ -443,7 +1550,7 @@ impl<const SIZE: usize> SmallString<SIZE> {
             del_bytes: usize,
         }
 
-        impl<'a, const SIZE: usize> Drop for SetLenOnDrop<'_, SIZE> {
+        impl<const SIZE: usize> Drop for SetLenOnDrop<'_, SIZE> {
             fn drop(&mut self) {
                 let new_len = self.idx - self.del_bytes;
                 debug_assert!(new_len <= self.s.len());
@@ -473,9 +1580,10 @@ impl<const SIZE: usize> SmallString<SIZE> {
                 guard.del_bytes += ch_len;
             } else if guard.del_bytes > 0 {
                 unsafe {
+                    let ptr = guard.s.data.as_mut_ptr();
                     ptr::copy(
-                        guard.s.data.as_ptr().add(guard.idx),
-                        guard.s.data.as_mut_ptr().add(guard.idx - guard.del_bytes),
+                        ptr.add(guard.idx),
+                        ptr.add(guard.idx - guard.del_bytes),
                         ch_len,
                     );
                 }
@@ -488,9 +1596,126 @@ impl<const SIZE: usize> SmallString<SIZE> {
         drop(guard);
     }
 
-    #[inline(always)]
-    fn as_mut_ptr(&mut self) -> *mut u8 {
-        self.as_ptr() as *mut u8
+    /// 返回该字符串的小写形式，结果在足够短时存储在内联缓冲区中。
+    ///
+    /// 与`str::to_lowercase`完全一致，包括词尾`Σ`转换为`ς`的规则。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let s: SmallString<16> = SmallString::from("Content-Type");
+    ///
+    /// assert_eq!(s.to_lowercase_small(), "content-type");
+    /// ```
+    pub fn to_lowercase_small(&self) -> SmallString<SIZE> {
+        self.map_chars(lowercase())
+    }
+
+    /// 返回该字符串的大写形式，结果在足够短时存储在内联缓冲区中。
+    ///
+    /// 与`str::to_uppercase`完全一致，结果的长度可能改变，例如`ß`转换为`SS`。
+    pub fn to_uppercase_small(&self) -> SmallString<SIZE> {
+        self.map_chars(|ch, _| ch.to_uppercase())
+    }
+
+    /// 返回该字符串的大小写折叠形式，用于不区分大小写的比较。
+    ///
+    /// 每个字符先按`char::to_uppercase`再按`char::to_lowercase`转换，例如`ß`、`ẞ`、`SS`和`ss`
+    /// 都折叠为`ss`，`ς`、`Σ`和`σ`都折叠为`σ`。对折叠的结果再次折叠不会改变它。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let a: SmallString<16> = SmallString::from("Straße");
+    /// let b: SmallString<16> = SmallString::from("STRASSE");
+    ///
+    /// assert_eq!(a.case_fold(), b.case_fold());
+    /// ```
+    pub fn case_fold(&self) -> SmallString<SIZE> {
+        self.map_chars(|ch, _| fold_char(ch))
+    }
+
+    /// 将该字符串就地转换为小写，参见 [`to_lowercase_small`](#method.to_lowercase_small)。
+    ///
+    /// 在遇到第一个转换后 UTF-8 长度改变的字符之前直接覆盖原缓冲区，此后的部分转换后再追加，
+    /// 临时结果在足够短时存储在栈上。
+    pub fn to_lowercase_in_place(&mut self) {
+        self.map_chars_in_place(lowercase());
+    }
+
+    /// 将该字符串就地转换为大写，参见 [`to_uppercase_small`](#method.to_uppercase_small)。
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use smallstr::SmallString;
+    ///
+    /// let mut s: SmallString<16> = SmallString::from("straße");
+    ///
+    /// s.to_uppercase_in_place();
+    ///
+    /// assert_eq!(s, "STRASSE");
+    /// ```
+    pub fn to_uppercase_in_place(&mut self) {
+        self.map_chars_in_place(|ch, _| ch.to_uppercase());
+    }
+
+    /// 将该字符串就地进行大小写折叠，参见 [`case_fold`](#method.case_fold)。
+    pub fn case_fold_in_place(&mut self) {
+        self.map_chars_in_place(|ch, _| fold_char(ch));
+    }
+
+    /// 按顺序映射每个字符，`f`的第二个参数是该字符之后尚未映射的原始内容。
+    fn map_chars<I, F>(&self, mut f: F) -> SmallString<SIZE>
+    where
+        I: Iterator<Item = char>,
+        F: FnMut(char, &str) -> I,
+    {
+        let mut s = SmallString::with_capacity(self.len());
+        for (i, ch) in self.char_indices() {
+            s.extend(f(ch, &self[i + ch.len_utf8()..]));
+        }
+        s
+    }
+
+    /// 就地按顺序映射每个字符，每个字符只调用一次`f`，参数同 [`map_chars`](#method.map_chars)。
+    fn map_chars_in_place<I, F>(&mut self, mut f: F)
+    where
+        I: Iterator<Item = char>,
+        F: FnMut(char, &str) -> I,
+    {
+        let mut idx = 0;
+        let mut tail = SmallString::<SIZE>::new();
+
+        // 映射为长度相同的单个字符时直接覆盖
+        while let Some(ch) = self[idx..].chars().next() {
+            let ch_len = ch.len_utf8();
+            let mut mapped = f(ch, &self[idx + ch_len..]);
+            match (mapped.next(), mapped.next()) {
+                (Some(m), None) if m.len_utf8() == ch_len => unsafe {
+                    m.encode_utf8(&mut self.as_bytes_mut()[idx..idx + ch_len]);
+                },
+                (first, second) => {
+                    // 长度改变，此后的部分转换到临时字符串中
+                    tail.extend(first.into_iter().chain(second).chain(mapped));
+                    let rest = &self[idx + ch_len..];
+                    for (i, ch) in rest.char_indices() {
+                        tail.extend(f(ch, &rest[i + ch.len_utf8()..]));
+                    }
+                    break;
+                }
+            }
+            idx += ch_len;
+        }
+
+        if idx < self.len() {
+            self.truncate(idx);
+            self.push_str(&tail);
+        }
     }
 }
 
@@ -604,7 +1829,7 @@ impl<const SIZE: usize> From<char> for SmallString<SIZE> {
     }
 }
 
-impl<'a, const SIZE: usize> From<&'a str> for SmallString<SIZE> {
+impl<const SIZE: usize> From<&str> for SmallString<SIZE> {
     #[inline(always)]
     fn from(s: &str) -> SmallString<SIZE> {
         SmallString::from_str(s)
@@ -625,6 +1850,27 @@ impl<const SIZE: usize> From<String> for SmallString<SIZE> {
     }
 }
 
+impl<const SIZE: usize> TryFrom<&[u8]> for SmallString<SIZE> {
+    type Error = Utf8Error;
+
+    #[inline(always)]
+    fn try_from(value: &[u8]) -> Result<SmallString<SIZE>, Utf8Error> {
+        utf8::from_utf8(value).map(SmallString::from_str)
+    }
+}
+
+impl<const SIZE: usize> TryFrom<Vec<u8>> for SmallString<SIZE> {
+    type Error = FromUtf8Error<SIZE>;
+
+    /// 复用`Vec`的分配，与 [`from_string`] 相同。
+    ///
+    /// [`from_string`]: struct.SmallString.html#method.from_string
+    #[inline(always)]
+    fn try_from(value: Vec<u8>) -> Result<SmallString<SIZE>, FromUtf8Error<SIZE>> {
+        SmallString::from_utf8(SmallVec::from_vec(value))
+    }
+}
+
 impl<'a, const SIZE: usize> From<Cow<'a, str>> for SmallString<SIZE> {
     fn from(value: Cow<'a, str>) -> Self {
         match value {
@@ -634,30 +1880,21 @@ impl<'a, const SIZE: usize> From<Cow<'a, str>> for SmallString<SIZE> {
     }
 }
 
-macro_rules! impl_index_str {
-    ($index_type: ty) => {
-        impl<const SIZE: usize> ops::Index<$index_type> for SmallString<SIZE> {
-            type Output = str;
-
-            #[inline(always)]
-            fn index(&self, index: $index_type) -> &str {
-                &self.as_str()[index]
-            }
-        }
+impl<I: SliceIndex<str>, const SIZE: usize> ops::Index<I> for SmallString<SIZE> {
+    type Output = I::Output;
 
-        impl<const SIZE: usize> ops::IndexMut<$index_type> for SmallString<SIZE> {
-            #[inline(always)]
-            fn index_mut(&mut self, index: $index_type) -> &mut str {
-                &mut self.as_mut_str()[index]
-            }
-        }
-    };
+    #[inline(always)]
+    fn index(&self, index: I) -> &I::Output {
+        &self.as_str()[index]
+    }
 }
 
-impl_index_str!(ops::Range<usize>);
-impl_index_str!(ops::RangeFrom<usize>);
-impl_index_str!(ops::RangeTo<usize>);
-impl_index_str!(ops::RangeFull);
+impl<I: SliceIndex<str>, const SIZE: usize> ops::IndexMut<I> for SmallString<SIZE> {
+    #[inline(always)]
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        &mut self.as_mut_str()[index]
+    }
+}
 
 impl<const SIZE: usize> FromIterator<char> for SmallString<SIZE> {
     fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> SmallString<SIZE> {
@@ -761,12 +1998,28 @@ macro_rules! eq_str {
         impl<'a, const SIZE: usize> PartialEq<$rhs> for SmallString<SIZE> {
             #[inline(always)]
             fn eq(&self, rhs: &$rhs) -> bool {
-                &self[..] == &rhs[..]
+                self[..] == rhs[..]
+            }
+        }
+
+        impl<'a, const SIZE: usize> PartialEq<SmallString<SIZE>> for $rhs {
+            #[inline(always)]
+            fn eq(&self, rhs: &SmallString<SIZE>) -> bool {
+                self[..] == rhs[..]
             }
+        }
+
+        impl<'a, const SIZE: usize> PartialOrd<$rhs> for SmallString<SIZE> {
+            #[inline(always)]
+            fn partial_cmp(&self, rhs: &$rhs) -> Option<Ordering> {
+                Some(self[..].cmp(&rhs[..]))
+            }
+        }
 
+        impl<'a, const SIZE: usize> PartialOrd<SmallString<SIZE>> for $rhs {
             #[inline(always)]
-            fn ne(&self, rhs: &$rhs) -> bool {
-                &self[..] != &rhs[..]
+            fn partial_cmp(&self, rhs: &SmallString<SIZE>) -> Option<Ordering> {
+                Some(self[..].cmp(&rhs[..]))
             }
         }
     };
@@ -777,90 +2030,197 @@ eq_str!(&'a str);
 eq_str!(String);
 eq_str!(Cow<'a, str>);
 
+/// 与`OsStr`比较时，将字符串视为`OsStr`。
 #[cfg(feature = "ffi")]
-impl<const SIZE: usize> PartialEq<OsStr> for SmallString<SIZE> {
-    #[inline(always)]
-    fn eq(&self, rhs: &OsStr) -> bool {
-        &self[..] == rhs
-    }
+macro_rules! eq_os_str {
+    ( $rhs:ty ) => {
+        impl<'a, const SIZE: usize> PartialEq<$rhs> for SmallString<SIZE> {
+            #[inline(always)]
+            fn eq(&self, rhs: &$rhs) -> bool {
+                OsStr::new(&self[..]) == AsRef::<OsStr>::as_ref(rhs)
+            }
+        }
 
-    #[inline(always)]
-    fn ne(&self, rhs: &OsStr) -> bool {
-        &self[..] != rhs
-    }
+        impl<'a, const SIZE: usize> PartialEq<SmallString<SIZE>> for $rhs {
+            #[inline(always)]
+            fn eq(&self, rhs: &SmallString<SIZE>) -> bool {
+                AsRef::<OsStr>::as_ref(self) == OsStr::new(&rhs[..])
+            }
+        }
+
+        impl<'a, const SIZE: usize> PartialOrd<$rhs> for SmallString<SIZE> {
+            #[inline(always)]
+            fn partial_cmp(&self, rhs: &$rhs) -> Option<Ordering> {
+                OsStr::new(&self[..]).partial_cmp(AsRef::<OsStr>::as_ref(rhs))
+            }
+        }
+
+        impl<'a, const SIZE: usize> PartialOrd<SmallString<SIZE>> for $rhs {
+            #[inline(always)]
+            fn partial_cmp(&self, rhs: &SmallString<SIZE>) -> Option<Ordering> {
+                AsRef::<OsStr>::as_ref(self).partial_cmp(OsStr::new(&rhs[..]))
+            }
+        }
+    };
 }
 
 #[cfg(feature = "ffi")]
-impl<'a, const SIZE: usize> PartialEq<&'a OsStr> for SmallString<SIZE> {
-    #[inline(always)]
-    fn eq(&self, rhs: &&OsStr) -> bool {
-        &self[..] == *rhs
-    }
+eq_os_str!(OsStr);
+#[cfg(feature = "ffi")]
+eq_os_str!(&'a OsStr);
+#[cfg(feature = "ffi")]
+eq_os_str!(OsString);
+#[cfg(feature = "ffi")]
+eq_os_str!(Cow<'a, OsStr>);
 
+impl<const SIZE: usize> PartialEq for SmallString<SIZE> {
     #[inline(always)]
-    fn ne(&self, rhs: &&OsStr) -> bool {
-        &self[..] != *rhs
+    fn eq(&self, rhs: &SmallString<SIZE>) -> bool {
+        self[..] == rhs[..]
     }
 }
 
-#[cfg(feature = "ffi")]
-impl<const SIZE: usize> PartialEq<OsString> for SmallString<SIZE> {
+impl<const SIZE: usize> Eq for SmallString<SIZE> {}
+
+impl<const SIZE: usize> PartialOrd for SmallString<SIZE> {
     #[inline(always)]
-    fn eq(&self, rhs: &OsString) -> bool {
-        &self[..] == rhs
+    fn partial_cmp(&self, rhs: &SmallString<SIZE>) -> Option<Ordering> {
+        Some(self.cmp(rhs))
     }
+}
 
+impl<const SIZE: usize> Ord for SmallString<SIZE> {
     #[inline(always)]
-    fn ne(&self, rhs: &OsString) -> bool {
-        &self[..] != rhs
+    fn cmp(&self, rhs: &SmallString<SIZE>) -> Ordering {
+        self[..].cmp(&rhs[..])
     }
 }
 
-#[cfg(feature = "ffi")]
-impl<'a, const SIZE: usize> PartialEq<Cow<'a, OsStr>> for SmallString<SIZE> {
+/// 哈希值与内容相同的`str`一致，与`SIZE`无关。
+///
+/// 因此以某个`SIZE`为键的哈希表可以通过`Borrow<str>`用`&str`查询；
+/// 启用`hashbrown`功能后，也可以用 [`AnySize`] 包装其他`SIZE`的键直接查询。
+impl<const SIZE: usize> Hash for SmallString<SIZE> {
     #[inline(always)]
-    fn eq(&self, rhs: &Cow<OsStr>) -> bool {
-        self[..] == **rhs
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self[..].hash(state)
     }
+}
 
+/// 用于以任意`SIZE`的`SmallString`查询`hashbrown`哈希表的键包装。
+///
+/// 哈希值与被包装的字符串一致，并为所有`SIZE`的`SmallString`实现了
+/// `hashbrown::Equivalent`，因此无需先转换为`&str`。
+///
+/// # Examples
+///
+/// ```
+/// use hashbrown::HashMap;
+/// use smallstr::{AnySize, SmallString};
+///
+/// let mut map: HashMap<SmallString<32>, i32> = HashMap::new();
+/// map.insert(SmallString::from("foo"), 1);
+///
+/// let key: SmallString<4> = SmallString::from("foo");
+/// assert_eq!(map.get(&AnySize(&key)), Some(&1));
+/// ```
+#[cfg(feature = "hashbrown")]
+#[derive(Clone, Copy, Debug)]
+pub struct AnySize<'a, const SIZE: usize>(pub &'a SmallString<SIZE>);
+
+#[cfg(feature = "hashbrown")]
+impl<const SIZE: usize> Hash for AnySize<'_, SIZE> {
     #[inline(always)]
-    fn ne(&self, rhs: &Cow<OsStr>) -> bool {
-        self[..] != **rhs
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
     }
 }
 
-impl<const SIZE: usize> PartialEq<SmallString<SIZE>> for SmallString<SIZE> {
+#[cfg(feature = "hashbrown")]
+impl<const A: usize, const B: usize> hashbrown::Equivalent<SmallString<B>> for AnySize<'_, A> {
     #[inline(always)]
-    fn eq(&self, rhs: &SmallString<SIZE>) -> bool {
-        &self[..] == &rhs[..]
+    fn equivalent(&self, key: &SmallString<B>) -> bool {
+        self.0.eq_any_size(key)
     }
+}
 
-    #[inline(always)]
-    fn ne(&self, rhs: &SmallString<SIZE>) -> bool {
-        &self[..] != &rhs[..]
+/// 返回按顺序转换小写的映射函数，与`str::to_lowercase`一样处理词尾的`Σ`：
+/// 前面（跳过`Case_Ignorable`字符后）是`Cased`字符、后面不是时转换为`ς`。
+fn lowercase() -> impl FnMut(char, &str) -> ToLowercase {
+    // 已经映射过的原始字符是否满足`case_ignorable_then_cased`
+    let mut cased_before = false;
+    move |ch, rest| {
+        let is_final =
+            ch == 'Σ' && cased_before && !unicode::case_ignorable_then_cased(rest.chars());
+        if !unicode::is_case_ignorable(ch) {
+            cased_before = unicode::is_cased(ch);
+        }
+        if is_final { 'ς' } else { ch }.to_lowercase()
     }
 }
 
-impl<const SIZE: usize> Eq for SmallString<SIZE> {}
+/// 大小写折叠单个字符：先转换为大写再转换为小写。
+///
+/// `ẞ`（U+1E9E）转换为小写是`ß`，再次折叠才会得到`ss`，因此先将其替换为`ß`，
+/// 使折叠的结果不再变化。其余字符经过一次转换后都已是不动点。
+#[inline(always)]
+fn fold_char(ch: char) -> impl Iterator<Item = char> {
+    let ch = if ch == '\u{1E9E}' { 'ß' } else { ch };
+    ch.to_uppercase().flat_map(char::to_lowercase)
+}
 
-impl<const SIZE: usize> PartialOrd for SmallString<SIZE> {
-    #[inline(always)]
-    fn partial_cmp(&self, rhs: &SmallString<SIZE>) -> Option<Ordering> {
-        self[..].partial_cmp(&rhs[..])
-    }
+/// 将`range`解析为`0..len`内的`(start, end)`，越界时与切片索引一样 panic。
+pub(crate) fn slice_range<R: ops::RangeBounds<usize>>(range: R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        ops::Bound::Included(&n) => n,
+        ops::Bound::Excluded(&n) => n
+            .checked_add(1)
+            .expect("attempted to index slice from after maximum usize"),
+        ops::Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        ops::Bound::Included(&n) => n
+            .checked_add(1)
+            .expect("attempted to index slice up to maximum usize"),
+        ops::Bound::Excluded(&n) => n,
+        ops::Bound::Unbounded => len,
+    };
+
+    assert!(
+        start <= end,
+        "slice index starts at {} but ends at {}",
+        start,
+        end
+    );
+    assert!(
+        end <= len,
+        "range end index {} out of range for slice of length {}",
+        end,
+        len
+    );
+
+    (start, end)
 }
 
-impl<const SIZE: usize> Ord for SmallString<SIZE> {
+/// 可以被 [`Drain`] 移除一段内容的字符串存储。
+pub(crate) trait DrainTarget {
+    fn as_mut_str(&mut self) -> &mut str;
+
+    /// # Safety
+    ///
+    /// `len`不能超过当前长度，并且必须位于`char`边界上。
+    unsafe fn set_len(&mut self, len: usize);
+}
+
+impl<const SIZE: usize> DrainTarget for SmallString<SIZE> {
     #[inline(always)]
-    fn cmp(&self, rhs: &SmallString<SIZE>) -> Ordering {
-        self[..].cmp(&rhs[..])
+    fn as_mut_str(&mut self) -> &mut str {
+        SmallString::as_mut_str(self)
     }
-}
 
-impl<const SIZE: usize> Hash for SmallString<SIZE> {
     #[inline(always)]
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self[..].hash(state)
+    unsafe fn set_len(&mut self, len: usize) {
+        self.data.set_len(len);
     }
 }
 
@@ -871,9 +2231,69 @@ impl<const SIZE: usize> Hash for SmallString<SIZE> {
 /// [`drain`]: struct.SmallString.html#method.drain
 /// [`SmallString`]: struct.SmallString.html
 pub struct Drain<'a> {
+    string: *mut (dyn DrainTarget + 'a),
+    start: usize,
+    end: usize,
     iter: Chars<'a>,
 }
 
+unsafe impl Send for Drain<'_> {}
+unsafe impl Sync for Drain<'_> {}
+
+impl<'a> Drain<'a> {
+    pub(crate) fn new<R: ops::RangeBounds<usize>>(
+        string: &'a mut (dyn DrainTarget + 'a),
+        range: R,
+    ) -> Drain<'a> {
+        // 先转换为裸指针，迭代器和`Drop`中对剩余内容的访问都由它派生，
+        // 之后不再通过原来的`&mut`访问字符串
+        let string: *mut (dyn DrainTarget + 'a) = string;
+        let s = unsafe { (*string).as_mut_str() };
+        let (start, end) = slice_range(range, s.len());
+        assert!(s.is_char_boundary(start));
+        assert!(s.is_char_boundary(end));
+
+        // 迭代器直接引用字符串的内存，被移除的范围在`Drain`被丢弃之前保持不变
+        let iter = unsafe {
+            let slice = slice::from_raw_parts(s.as_ptr().add(start), end - start);
+            str::from_utf8_unchecked(slice).chars()
+        };
+
+        Drain {
+            string,
+            start,
+            end,
+            iter,
+        }
+    }
+
+    /// 以字符串切片的形式返回尚未迭代的内容。
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        self.iter.as_str()
+    }
+}
+
+impl Drop for Drain<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            let string = &mut *self.string;
+            let s = string.as_mut_str();
+            let len = s.len();
+            let ptr = s.as_mut_ptr();
+
+            ptr::copy(ptr.add(self.end), ptr.add(self.start), len - self.end);
+            string.set_len(len - (self.end - self.start));
+        }
+    }
+}
+
+impl fmt::Debug for Drain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.as_str()).finish()
+    }
+}
+
 impl<'a> Iterator for Drain<'a> {
     type Item = char;
 
@@ -894,3 +2314,96 @@ impl<'a> DoubleEndedIterator for Drain<'a> {
         self.iter.next_back()
     }
 }
+
+impl FusedIterator for Drain<'_> {}
+
+impl<const SIZE: usize> IntoIterator for SmallString<SIZE> {
+    type Item = char;
+    type IntoIter = IntoChars<SIZE>;
+
+    #[inline(always)]
+    fn into_iter(self) -> IntoChars<SIZE> {
+        let back = self.len();
+
+        IntoChars {
+            string: self,
+            front: 0,
+            back,
+        }
+    }
+}
+
+impl<'a, const SIZE: usize> IntoIterator for &'a SmallString<SIZE> {
+    type Item = char;
+    type IntoIter = Chars<'a>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Chars<'a> {
+        self.chars()
+    }
+}
+
+/// 按值迭代 `SmallString` 中各个`char`的迭代器。
+///
+/// 该结构是由 [`SmallString`] 的`into_iter`方法创建的，字符串的缓冲区（包括内联缓冲区）
+/// 由迭代器持有，直到迭代器被丢弃。
+///
+/// [`SmallString`]: struct.SmallString.html
+#[derive(Clone)]
+pub struct IntoChars<const SIZE: usize> {
+    string: SmallString<SIZE>,
+    front: usize,
+    back: usize,
+}
+
+impl<const SIZE: usize> IntoChars<SIZE> {
+    /// 以字符串切片的形式返回尚未迭代的内容。
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        // `front`和`back`始终位于`char`边界上
+        unsafe { self.string.get_unchecked(self.front..self.back) }
+    }
+}
+
+impl<const SIZE: usize> fmt::Debug for IntoChars<SIZE> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IntoChars").field(&self.as_str()).finish()
+    }
+}
+
+impl<const SIZE: usize> Iterator for IntoChars<SIZE> {
+    type Item = char;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next()?;
+        self.front += ch.len_utf8();
+        Some(ch)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.as_str().chars().size_hint()
+    }
+
+    #[inline(always)]
+    fn count(self) -> usize {
+        self.as_str().chars().count()
+    }
+
+    #[inline(always)]
+    fn last(mut self) -> Option<char> {
+        self.next_back()
+    }
+}
+
+impl<const SIZE: usize> DoubleEndedIterator for IntoChars<SIZE> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        self.back -= ch.len_utf8();
+        Some(ch)
+    }
+}
+
+impl<const SIZE: usize> FusedIterator for IntoChars<SIZE> {}
@@ -0,0 +1,51 @@
+//! 字节构造函数使用的 UTF-8 校验。
+//!
+//! 启用`simd-utf8`功能时先使用`simdutf8`的 SIMD 校验器；在同时启用`std`时，
+//! 它会在运行时检测 CPU 支持的指令集，不支持时退化为标量实现。校验失败时再交给
+//! `core::str::from_utf8`生成错误，因此返回的`Utf8Error`与未启用该功能时完全一致。
+
+use core::str;
+#[cfg(feature = "simd-utf8")]
+use core::str::Utf8Error;
+
+/// 不超过该长度的输入直接使用标量实现。`benches/utf8.rs`直接比较两者：
+/// 32 字节及以下标量实现更快，64 字节时两者相当，更长的输入 SIMD 校验明显更快。
+#[cfg(feature = "simd-utf8")]
+const SIMD_THRESHOLD: usize = 32;
+
+/// 校验`v`是否为有效的 UTF-8，与`core::str::from_utf8`的结果相同。
+#[cfg(feature = "simd-utf8")]
+#[inline(always)]
+pub(crate) fn from_utf8(v: &[u8]) -> Result<&str, Utf8Error> {
+    if v.len() <= SIMD_THRESHOLD {
+        return str::from_utf8(v);
+    }
+
+    match simdutf8::basic::from_utf8(v) {
+        Ok(s) => Ok(s),
+        Err(_) => str::from_utf8(v),
+    }
+}
+
+#[cfg(not(feature = "simd-utf8"))]
+pub(crate) use core::str::from_utf8;
+
+/// 返回`v`开头已确认有效的 UTF-8 字节数，整个切片有效时为`v.len()`。
+///
+/// 与 [`from_utf8`] 不同，SIMD 校验失败时不会再用标量实现定位错误，而是返回`0`，
+/// 供有损转换直接从头处理。
+#[inline(always)]
+pub(crate) fn valid_up_to(v: &[u8]) -> usize {
+    #[cfg(feature = "simd-utf8")]
+    if v.len() > SIMD_THRESHOLD {
+        return match simdutf8::basic::from_utf8(v) {
+            Ok(_) => v.len(),
+            Err(_) => 0,
+        };
+    }
+
+    match str::from_utf8(v) {
+        Ok(_) => v.len(),
+        Err(error) => error.valid_up_to(),
+    }
+}
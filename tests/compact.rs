@@ -0,0 +1,159 @@
+#![no_std]
+
+extern crate alloc;
+use alloc::string::{String, ToString};
+use core::{fmt::Write, mem::size_of};
+
+use smallstr::{CompactSmallString, TryReserveError};
+
+const LONG: &str = "a string that is too long to be stored inline";
+
+#[test]
+fn test_size() {
+    assert_eq!(size_of::<CompactSmallString>(), size_of::<String>());
+    assert_eq!(size_of::<Option<CompactSmallString>>(), size_of::<String>());
+}
+
+#[test]
+fn test_inline_and_spill() {
+    let inline_size = 3 * size_of::<usize>() - 1;
+    let mut s = CompactSmallString::new();
+    assert_eq!(s.inline_size(), inline_size);
+    assert_eq!(s.capacity(), inline_size);
+
+    for i in 0..inline_size {
+        s.push((b'a' + i as u8 % 26) as char);
+        assert_eq!(s.len(), i + 1);
+        assert!(!s.spilled());
+    }
+
+    let before = s.to_string();
+    s.push('!');
+    assert!(s.spilled());
+    assert_eq!(s.len(), inline_size + 1);
+    assert_eq!(s, before + "!");
+
+    s.truncate(3);
+    assert!(s.spilled());
+    s.shrink_to_fit();
+    assert!(!s.spilled());
+    assert_eq!(s, "abc");
+}
+
+#[test]
+fn test_edit() {
+    let mut s = CompactSmallString::from("héllo");
+    s.insert(0, 'α');
+    s.insert_str(3, LONG);
+    assert_eq!(s.as_str(), ["αh", LONG, "éllo"].concat());
+
+    assert_eq!(s.remove(0), 'α');
+    assert_eq!(s.pop(), Some('o'));
+    s.retain(|ch| ch != 'l' && ch != ' ');
+    assert_eq!(s, "hastringthatistooongtobestoredinineé");
+
+    let mut small = CompactSmallString::from("abcabc");
+    small.retain(|ch| ch != 'b');
+    assert_eq!(small, "acac");
+
+    assert_eq!(
+        s.drain(..).collect::<String>(),
+        "hastringthatistooongtobestoredinineé"
+    );
+    assert!(s.is_empty());
+    s.clear();
+    assert_eq!(s.pop(), None);
+}
+
+#[test]
+fn test_capacity() {
+    let mut s = CompactSmallString::with_capacity(100);
+    assert!(s.spilled());
+    assert_eq!(s.capacity(), 100);
+
+    s.push_str("foo");
+    s.shrink_to(50);
+    assert_eq!(s.capacity(), 50);
+    s.shrink_to(0);
+    assert!(!s.spilled());
+
+    s.reserve_exact(40);
+    assert_eq!(s.capacity(), 43);
+    s.reserve(100);
+    assert_eq!(s.capacity(), 128);
+    assert_eq!(s, "foo");
+
+    assert_eq!(
+        s.try_reserve(usize::MAX),
+        Err(TryReserveError::CapacityOverflow)
+    );
+    assert_eq!(
+        s.try_reserve_exact(usize::MAX >> 4),
+        Err(TryReserveError::CapacityOverflow)
+    );
+    assert_eq!(s, "foo");
+}
+
+#[test]
+fn test_max_capacity() {
+    const MAX: usize = CompactSmallString::MAX_CAPACITY;
+    assert_eq!(MAX, usize::MAX >> 8);
+
+    let mut s = CompactSmallString::from("foo");
+    assert_eq!(s.try_grow(MAX + 1), Err(TryReserveError::CapacityOverflow));
+    assert_eq!(
+        s.try_reserve_exact(MAX - 2),
+        Err(TryReserveError::CapacityOverflow)
+    );
+    assert_eq!(s, "foo");
+    assert!(!s.spilled());
+}
+
+#[cfg(target_pointer_width = "32")]
+#[test]
+fn test_max_capacity_32bit() {
+    const MAX: usize = CompactSmallString::MAX_CAPACITY;
+    assert_eq!(MAX, (1 << 24) - 1);
+
+    let mut s = CompactSmallString::from(LONG);
+    assert_eq!(s.try_reserve_exact(MAX - LONG.len()), Ok(()));
+    assert_eq!(s.capacity(), MAX);
+    assert_eq!(s.try_reserve(MAX), Err(TryReserveError::CapacityOverflow));
+
+    // 容量超过上限的`String`会被复制到新的分配中
+    let mut big = String::with_capacity(MAX + 1);
+    big.push_str(LONG);
+    let s = CompactSmallString::from_string(big);
+    assert_eq!(s, LONG);
+    assert!(s.capacity() <= MAX);
+}
+
+#[test]
+fn test_conversions() {
+    let s = CompactSmallString::from_string(LONG.to_string());
+    assert!(s.spilled());
+    let clone = s.clone();
+    assert_eq!(clone, s);
+    assert_eq!(s.into_string(), LONG);
+    assert_eq!(String::from(clone), LONG);
+
+    let s = CompactSmallString::from_string("short".to_string());
+    assert!(!s.spilled());
+    assert_eq!(&*s.into_boxed_str(), "short");
+
+    let s: CompactSmallString = ["foo", "bar"].iter().copied().collect();
+    assert_eq!(s, "foobar");
+    assert_eq!(Some(s.clone()), Some(CompactSmallString::from("foobar")));
+}
+
+#[test]
+fn test_formatting() {
+    let mut s = CompactSmallString::new();
+    s.push_integer(-42);
+    s.push(' ');
+    s.push_hex_padded(0xbeef_u32, 8, '0');
+    s.push(' ');
+    s.push_f64(0.1 + 0.2);
+    write!(s, " {}", 7).unwrap();
+    assert_eq!(s, "-42 0000beef 0.30000000000000004 7");
+}
@@ -0,0 +1,89 @@
+#![no_std]
+
+extern crate alloc;
+use alloc::{ffi::CString, string::String};
+use core::{convert::TryFrom, ffi::CStr};
+
+use smallstr::{SmallCString, SmallString};
+
+#[test]
+fn test_new() {
+    let s: SmallCString<8> = SmallCString::new();
+    assert!(s.is_empty());
+    assert_eq!(s.as_bytes_with_nul(), b"\0");
+    assert_eq!(s, SmallCString::default());
+    assert_eq!(s.as_c_str(), SmallCString::<16>::default().as_c_str());
+
+    let s: SmallCString<8> = SmallCString::from_bytes(b"1234567").unwrap();
+    assert_eq!(s.len(), 7);
+    assert_eq!(s.as_bytes(), b"1234567");
+    assert!(!s.spilled());
+
+    let s: SmallCString<8> = SmallCString::from_bytes(b"12345678").unwrap();
+    assert!(s.spilled());
+    assert_eq!(s.as_bytes_with_nul(), b"12345678\0");
+}
+
+#[test]
+fn test_nul_error() {
+    let error = SmallCString::<8>::from_bytes(b"ab\0cd").unwrap_err();
+    assert_eq!(error.nul_position(), 2);
+    assert_eq!(&error.into_bytes()[..], b"ab\0cd");
+
+    let error = SmallCString::<8>::try_from("\0").unwrap_err();
+    assert_eq!(error.nul_position(), 0);
+
+    let s: SmallString<8> = SmallString::from("a\0b");
+    let error = SmallCString::try_from(s).unwrap_err();
+    assert_eq!(error.nul_position(), 1);
+}
+
+#[test]
+fn test_conversions() {
+    let s: SmallString<8> = SmallString::from("héllo");
+    let c = SmallCString::from_small_string(s).unwrap();
+    assert_eq!(
+        c.as_c_str(),
+        CStr::from_bytes_with_nul(b"h\xc3\xa9llo\0").unwrap()
+    );
+    assert_eq!(c.to_str(), Ok("héllo"));
+    assert_eq!(SmallString::try_from(c.clone()).unwrap(), "héllo");
+
+    let c: SmallCString<8> = SmallCString::from_bytes(b"\xff").unwrap();
+    assert!(c.to_str().is_err());
+    assert_eq!(
+        &c.into_small_string().unwrap_err().into_bytes()[..],
+        b"\xff"
+    );
+
+    let owned = CString::new("from a CString").unwrap();
+    let c: SmallCString<8> = SmallCString::from(owned.as_c_str());
+    assert_eq!(c, owned.as_c_str());
+    assert_eq!(c.to_string_lossy(), "from a CString");
+    assert_eq!(CString::from(c), owned);
+}
+
+// Miri 不支持调用外部 C 函数
+#[cfg(target_os = "linux")]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn test_libc() {
+    let mut name: SmallCString<32> = SmallCString::try_from("smallstr").unwrap();
+    assert!(!name.spilled());
+    assert_eq!(unsafe { libc::strlen(name.as_ptr()) }, 8);
+
+    let other: SmallCString<32> = SmallCString::try_from("smallvec").unwrap();
+    assert!(unsafe { libc::strcmp(name.as_ptr(), other.as_ptr()) } < 0);
+    assert!(name < other);
+
+    let same = CString::new("smallstr").unwrap();
+    assert_eq!(unsafe { libc::strcmp(name.as_ptr(), same.as_ptr()) }, 0);
+
+    let p = unsafe { libc::strchr(name.as_ptr(), b'l' as libc::c_int) };
+    assert_eq!(p as usize - name.as_ptr() as usize, 3);
+
+    let long: String = "x".repeat(100);
+    name = SmallCString::try_from(&long[..]).unwrap();
+    assert!(name.spilled());
+    assert_eq!(unsafe { libc::strlen(name.as_ptr()) }, 100);
+}
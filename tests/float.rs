@@ -0,0 +1,158 @@
+//! `push_f64`/`push_f32` 与 `core::fmt` 的差分测试。
+
+use smallstr::SmallString;
+
+/// 简单的 xorshift 伪随机数生成器，保证测试可重复。
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn check_f64(value: f64) {
+    let mut s: SmallString<4> = SmallString::from("a");
+    s.push_f64(value);
+    assert_eq!(s, format!("a{}", value), "{:e}", value);
+
+    for &precision in &[0, 1, 2, 3, 6, 17, 30] {
+        let mut s: SmallString<4> = SmallString::new();
+        s.push_f64_fixed(value, precision);
+        assert_eq!(s, format!("{:.*}", precision, value), "{:e}", value);
+    }
+}
+
+fn check_f32(value: f32) {
+    let mut s: SmallString<4> = SmallString::from("a");
+    s.push_f32(value);
+    assert_eq!(s, format!("a{}", value), "{:e}", value);
+
+    for &precision in &[0, 1, 2, 3, 9, 40] {
+        let mut s: SmallString<4> = SmallString::new();
+        s.push_f32_fixed(value, precision);
+        assert_eq!(s, format!("{:.*}", precision, value), "{:e}", value);
+    }
+}
+
+#[test]
+fn special_values() {
+    for &v in &[
+        0.0,
+        -0.0,
+        f64::NAN,
+        -f64::NAN,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::MIN,
+        f64::MAX,
+        f64::MIN_POSITIVE,
+        f64::EPSILON,
+        f64::from_bits(1),
+        f64::from_bits(0x000f_ffff_ffff_ffff),
+        1.0,
+        -1.0,
+        0.1,
+        0.3,
+        1e21,
+        1e22,
+        1e23,
+        1e-7,
+        123456789.125,
+        9007199254740993.0,
+    ] {
+        check_f64(v);
+    }
+
+    for &v in &[
+        0.0,
+        -0.0,
+        f32::NAN,
+        f32::INFINITY,
+        f32::NEG_INFINITY,
+        f32::MIN,
+        f32::MAX,
+        f32::MIN_POSITIVE,
+        f32::from_bits(1),
+        0.1,
+        16777217.0,
+        3.4e-39,
+    ] {
+        check_f32(v);
+    }
+}
+
+#[test]
+fn round_half_even() {
+    for &v in &[
+        0.5, 1.5, 2.5, -0.5, 0.125, 0.375, 9.5, 99.5, 999.95, -0.01, 0.045,
+    ] {
+        check_f64(v);
+    }
+}
+
+#[test]
+fn powers() {
+    let mut v = 1.0_f64;
+    for _ in 0..330 {
+        check_f64(v);
+        check_f64(1.0 / v);
+        v *= 10.0;
+    }
+
+    for e in -1074..1024 {
+        let v = 2.0_f64.powi(e);
+        check_f64(v);
+        check_f64(v - v * f64::EPSILON);
+    }
+
+    for e in -149..128 {
+        check_f32(2.0_f32.powi(e));
+    }
+}
+
+#[test]
+fn integers() {
+    for i in -1000..1000 {
+        check_f64(i as f64);
+        check_f32(i as f32);
+    }
+}
+
+#[test]
+fn random_f64() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..20_000 {
+        check_f64(f64::from_bits(rng.next()));
+    }
+}
+
+#[test]
+fn random_f32() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..20_000 {
+        check_f32(f32::from_bits(rng.next() as u32));
+    }
+}
+
+#[test]
+fn random_shortest() {
+    // 只检查最短表示，可以覆盖更多的值
+    let mut rng = Rng(0xdead_beef_cafe_babe);
+    for _ in 0..300_000 {
+        let bits = rng.next();
+
+        let value = f64::from_bits(bits);
+        let mut s: SmallString<32> = SmallString::new();
+        s.push_f64(value);
+        assert_eq!(s, format!("{}", value), "{:e}", value);
+
+        let value = f32::from_bits(bits as u32);
+        let mut s: SmallString<32> = SmallString::new();
+        s.push_f32(value);
+        assert_eq!(s, format!("{}", value), "{:e}", value);
+    }
+}
@@ -0,0 +1,150 @@
+//! `push_integer` 与 `core::fmt` 的差分测试。
+
+use core::fmt::{self, Display, Write};
+
+use smallstr::{SmallInteger, SmallString};
+
+/// 分别用不同的内联容量和前缀写入`value`，并与`core::fmt`的输出比较。
+fn check<T: SmallInteger + Display>(value: T) {
+    let mut expected = String::new();
+    write!(expected, "{}", value).unwrap();
+
+    let mut s: SmallString<1> = SmallString::new();
+    s.push_integer(value);
+    assert_eq!(s, expected);
+
+    let mut s: SmallString<2> = SmallString::from("x");
+    s.push_integer(value);
+    assert_eq!(s, format!("x{}", expected));
+
+    let mut s: SmallString<8> = SmallString::from("abc");
+    s.push_integer(value);
+    s.push_integer(value);
+    assert_eq!(s, format!("abc{0}{0}", expected));
+
+    let mut s: SmallString<64> = SmallString::new();
+    s.push_integer(value);
+    assert_eq!(s, expected);
+    assert!(!s.spilled());
+}
+
+macro_rules! boundaries {
+    ($($ty:ident),*) => {
+        $(
+            #[test]
+            fn $ty() {
+                check::<$ty>(0);
+                check::<$ty>(1);
+                check::<$ty>($ty::MIN);
+                check::<$ty>($ty::MAX);
+                check::<$ty>($ty::MIN + 1);
+                check::<$ty>($ty::MAX - 1);
+
+                // 10的幂附近
+                let mut pow: $ty = 1;
+                while let Some(next) = pow.checked_mul(10) {
+                    pow = next;
+                    check::<$ty>(pow - 1);
+                    check::<$ty>(pow);
+                    check::<$ty>(pow + 1);
+                    #[allow(unused_comparisons)]
+                    if $ty::MIN < 0 {
+                        check::<$ty>((0 as $ty).wrapping_sub(pow));
+                        check::<$ty>((0 as $ty).wrapping_sub(pow - 1));
+                    }
+                }
+
+                // 2的幂附近
+                let mut pow: $ty = 1;
+                while let Some(next) = pow.checked_mul(2) {
+                    pow = next;
+                    check::<$ty>(pow - 1);
+                    check::<$ty>(pow);
+                    check::<$ty>(pow + 1);
+                }
+            }
+        )*
+    };
+}
+
+boundaries!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[test]
+fn large_near_powers_of_ten() {
+    check(999_999_999_999_999_999_u64);
+    check(9_999_999_999_999_999_999_u64);
+    check(10_000_000_000_000_000_000_u64);
+    check(99_999_999_999_999_999_999_999_999_999_999_999_999_u128);
+    check(100_000_000_000_000_000_000_000_000_000_000_000_000_u128);
+}
+
+/// 检查十六进制、八进制和二进制的输出与`core::fmt`一致。
+fn check_radix<T: SmallInteger + fmt::LowerHex + fmt::UpperHex + fmt::Octal + fmt::Binary>(
+    value: T,
+) {
+    let mut s: SmallString<1> = SmallString::new();
+    s.push_hex(value);
+    assert_eq!(s, format!("{:x}", value));
+
+    let mut s: SmallString<1> = SmallString::new();
+    s.push_hex_upper(value);
+    assert_eq!(s, format!("{:X}", value));
+
+    let mut s: SmallString<2> = SmallString::from("x");
+    s.push_octal(value);
+    assert_eq!(s, format!("x{:o}", value));
+
+    let mut s: SmallString<2> = SmallString::from("x");
+    s.push_binary(value);
+    assert_eq!(s, format!("x{:b}", value));
+
+    let mut s: SmallString<8> = SmallString::from("ab");
+    s.push_hex_padded(value, 40, '0');
+    assert_eq!(s, format!("ab{:040x}", value));
+}
+
+#[test]
+fn radix() {
+    for &v in &[0_u8, 1, 7, 8, 15, 16, 0x7f, 0x80, u8::MAX] {
+        check_radix(v);
+    }
+    for &v in &[0_i32, -1, 1, i32::MIN, i32::MAX, 0x1234_5678] {
+        check_radix(v);
+    }
+    for &v in &[0_u64, u64::MAX, 1 << 63, 0xdead_beef_cafe_babe] {
+        check_radix(v);
+    }
+    for &v in &[0_u128, u128::MAX, 1 << 64, (1 << 64) - 1, 1 << 127] {
+        check_radix(v);
+    }
+    for &v in &[i128::MIN, -1_i128, i128::MAX] {
+        check_radix(v);
+    }
+    check_radix(usize::MAX);
+    check_radix(isize::MIN);
+}
+
+#[test]
+fn padded() {
+    let mut s: SmallString<4> = SmallString::new();
+    s.push_integer_padded(-42, 6, '0');
+    assert_eq!(s, format!("{:06}", -42));
+
+    let mut s: SmallString<4> = SmallString::new();
+    s.push_integer_padded(-42, 6, ' ');
+    assert_eq!(s, format!("{:>6}", -42));
+
+    let mut s: SmallString<4> = SmallString::from("[");
+    s.push_integer_padded(12345, 3, '0');
+    assert_eq!(s, "[12345");
+
+    let mut s: SmallString<4> = SmallString::new();
+    s.push_integer_padded(9_u8, 4, 'α');
+    assert_eq!(s, "ααα9");
+
+    let mut s: SmallString<4> = SmallString::new();
+    s.push_hex_upper_padded(0xab_u16, 4, '0');
+    s.push_octal_padded(8_u8, 3, '0');
+    s.push_binary_padded(5_u8, 8, '0');
+    assert_eq!(s, "00AB01000000101");
+}
@@ -1,4 +1,8 @@
+#![no_std]
+
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 use alloc::{
     borrow::{Cow, ToOwned},
     string::{String, ToString},
@@ -11,7 +15,7 @@ fn test_drain() {
     let mut s: SmallString<2> = SmallString::new();
 
     s.push('a');
-    assert_eq!(s.drain().collect::<String>(), "a");
+    assert_eq!(s.drain(..).collect::<String>(), "a");
     assert!(s.is_empty());
 
     // spilling the vec
@@ -19,7 +23,7 @@ fn test_drain() {
     s.push('y');
     s.push('z');
 
-    assert_eq!(s.drain().collect::<String>(), "xyz");
+    assert_eq!(s.drain(..).collect::<String>(), "xyz");
     assert!(s.is_empty());
 }
 
@@ -28,7 +32,7 @@ fn test_drain_rev() {
     let mut s: SmallString<2> = SmallString::new();
 
     s.push('a');
-    assert_eq!(s.drain().rev().collect::<String>(), "a");
+    assert_eq!(s.drain(..).rev().collect::<String>(), "a");
     assert!(s.is_empty());
 
     // spilling the vec
@@ -36,10 +40,45 @@ fn test_drain_rev() {
     s.push('y');
     s.push('z');
 
-    assert_eq!(s.drain().rev().collect::<String>(), "zyx");
+    assert_eq!(s.drain(..).rev().collect::<String>(), "zyx");
     assert!(s.is_empty());
 }
 
+#[test]
+fn test_drain_range() {
+    let mut s: SmallString<4> = SmallString::from("αβγδ");
+
+    let mut drain = s.drain(2..6);
+    assert_eq!(drain.as_str(), "βγ");
+    assert_eq!(drain.next(), Some('β'));
+    drop(drain);
+    assert_eq!(s, "αδ");
+
+    assert_eq!(s.drain(2..=3).collect::<String>(), "δ");
+    assert_eq!(s.drain(..0).count(), 0);
+    assert_eq!(s, "α");
+
+    let mut s: SmallString<4> = SmallString::from("foobar");
+    core::mem::forget(s.drain(1..4));
+    assert_eq!(s, "foobar");
+}
+
+#[test]
+#[should_panic]
+fn test_drain_panic_char_boundary() {
+    let mut s: SmallString<8> = SmallString::from("αβγ");
+
+    s.drain(1..);
+}
+
+#[test]
+#[should_panic]
+fn test_drain_panic_out_of_range() {
+    let mut s: SmallString<8> = SmallString::from("foo");
+
+    s.drain(2..4);
+}
+
 #[test]
 fn test_eq() {
     let s: SmallString<4> = SmallString::from("foo");
@@ -48,6 +87,26 @@ fn test_eq() {
     assert_eq!(s, "foo");
     assert_eq!(s, "foo".to_owned());
     assert_eq!(s, Cow::Borrowed("foo"));
+
+    assert_eq!(*"foo", s);
+    assert_eq!("foo", s);
+    assert_eq!("foo".to_owned(), s);
+    assert_eq!(Cow::Borrowed("foo"), s);
+}
+
+#[test]
+fn test_cmp_str() {
+    let s: SmallString<4> = SmallString::from("foo");
+
+    assert!(s < *"goo");
+    assert!(*"goo" > s);
+    assert!(s > "eoo");
+    assert!("eoo" < s);
+    let owned = "foo".to_owned();
+    assert!(s <= owned);
+    assert!(owned >= s);
+    assert!(s < Cow::Borrowed("fooo"));
+    assert!(Cow::Borrowed("fooo") > s);
 }
 
 #[cfg(feature = "ffi")]
@@ -62,6 +121,20 @@ fn test_eq_os_str() {
     assert_eq!(s, *os_s);
     assert_eq!(s, os_s.to_owned());
     assert_eq!(s, Cow::Borrowed(os_s));
+
+    assert_eq!(os_s, s);
+    assert_eq!(*os_s, s);
+    assert_eq!(os_s.to_owned(), s);
+    assert_eq!(Cow::Borrowed(os_s), s);
+
+    let goo: &OsStr = "goo".as_ref();
+    assert!(s < goo);
+    assert!(goo > s);
+    let owned = goo.to_owned();
+    assert!(s < owned);
+    assert!(owned > s);
+    assert!(s < Cow::Borrowed(goo));
+    assert!(Cow::Borrowed(goo) > s);
 }
 
 #[test]
@@ -204,7 +277,6 @@ fn test_write() {
     assert_eq!(s, "foobar");
 }
 
-
 #[test]
 fn test_push_integer() {
     let mut s: SmallString<8> = SmallString::from("foo");
@@ -212,6 +284,37 @@ fn test_push_integer() {
     assert_eq!(s, "foo12345");
 }
 
+#[test]
+fn test_push_integer_signed() {
+    let mut s: SmallString<64> = SmallString::new();
+
+    s.push_integer(-7_i8);
+    s.push(' ');
+    s.push_integer(i8::MIN);
+    s.push(' ');
+    s.push_integer(i32::MIN);
+    s.push(' ');
+    s.push_integer(i64::MIN);
+    s.push(' ');
+    s.push_integer(0_isize);
+    assert_eq!(s, "-7 -128 -2147483648 -9223372036854775808 0");
+}
+
+#[test]
+fn test_push_integer_128() {
+    let mut s: SmallString<64> = SmallString::new();
+    s.push_integer(u128::MAX);
+    assert_eq!(s, u128::MAX.to_string());
+
+    let mut s: SmallString<64> = SmallString::new();
+    s.push_integer(i128::MIN);
+    assert_eq!(s, i128::MIN.to_string());
+
+    let mut s: SmallString<64> = SmallString::new();
+    s.push_integer(42_u128);
+    assert_eq!(s, "42");
+}
+
 // #[cfg(feature = "serde")]
 // #[test]
 // fn test_serde() {
@@ -233,3 +336,842 @@ fn test_push_integer() {
 //
 //     assert_eq!(small_str, decoded);
 // }
+
+#[test]
+fn test_try_push() {
+    let mut s: SmallString<4> = SmallString::from("ab");
+
+    assert_eq!(s.try_push('c'), Ok(()));
+    assert_eq!(s.try_insert(0, 'x'), Ok(()));
+    assert!(!s.spilled());
+
+    assert_eq!(s.try_push('α'), Ok(()));
+    assert_eq!(s.try_insert_str(1, "yz"), Ok(()));
+    assert_eq!(s.try_push_integer(-12), Ok(()));
+    assert_eq!(s.try_push_str("!"), Ok(()));
+    assert_eq!(s, "xyzabcα-12!");
+    assert!(s.spilled());
+}
+
+#[test]
+fn test_try_reserve() {
+    use smallstr::TryReserveError;
+
+    let mut s: SmallString<4> = SmallString::try_from_str("foo").unwrap();
+
+    assert_eq!(s.try_reserve(1), Ok(()));
+    assert!(!s.spilled());
+    assert_eq!(s.try_reserve_exact(10), Ok(()));
+    assert!(s.capacity() >= 13);
+
+    assert_eq!(
+        s.try_reserve(usize::MAX),
+        Err(TryReserveError::CapacityOverflow)
+    );
+    assert!(s.try_reserve_exact(isize::MAX as usize).is_err());
+    assert!(s.try_grow(usize::MAX).is_err());
+    assert_eq!(s, "foo");
+
+    assert_eq!(SmallString::<4>::try_from_str("abcd").unwrap(), "abcd");
+    assert!(SmallString::<4>::try_from_str("foobar").unwrap().spilled());
+}
+
+#[test]
+fn test_inline_string() {
+    use core::{convert::TryFrom, fmt::Write};
+    use smallstr::{CapacityError, InlineString};
+
+    let mut s: InlineString<8> = InlineString::try_from("foo").unwrap();
+
+    s.push('b');
+    s.insert_str(0, "<");
+    s.push_integer(42);
+    assert_eq!(s, "<foob42");
+    assert_eq!(s.try_push_str("ar"), Err(CapacityError));
+    assert!(write!(s, "{}", 12).is_err());
+    assert_eq!(s, "<foob42");
+    assert!(write!(s, "{}", 1).is_ok());
+    assert_eq!(s.len(), 8);
+    assert_eq!(s.pop(), Some('1'));
+
+    assert!(InlineString::<2>::try_from("abc").is_err());
+
+    let spilled: SmallString<2> = SmallString::from("abcd");
+    let spilled = InlineString::try_from(spilled).unwrap_err();
+    assert_eq!(spilled, "abcd");
+
+    let mut small: SmallString<4> = SmallString::from("abcdef");
+    small.truncate(2);
+    let inline = InlineString::try_from(small).unwrap();
+    assert_eq!(SmallString::from(inline.clone()), "ab");
+    assert!(!SmallString::from(inline).spilled());
+}
+
+#[test]
+fn test_inline_try_push() {
+    use smallstr::{CapacityError, InlineString};
+
+    let mut s: InlineString<4> = InlineString::from_str("ab");
+
+    assert_eq!(s.try_push('c'), Ok(()));
+    assert_eq!(s.try_push('α'), Err(CapacityError));
+    assert_eq!(s.try_push_str("de"), Err(CapacityError));
+    assert_eq!(s.try_insert(0, 'x'), Ok(()));
+    assert_eq!(s.try_push_integer(1), Err(CapacityError));
+    assert_eq!(s, "xabc");
+
+    let mut s: InlineString<4> = InlineString::new();
+    assert_eq!(s.try_push_integer(-123), Ok(()));
+    assert_eq!(s.try_insert_str(1, "0"), Err(CapacityError));
+    assert_eq!(s, "-123");
+
+    assert_eq!(InlineString::<4>::try_from_str("abcd").unwrap(), "abcd");
+    assert_eq!(InlineString::<4>::try_from_str("abcde"), Err(CapacityError));
+}
+
+#[test]
+fn test_inline_string_forwarded() {
+    use smallstr::{CapacityError, InlineString, TryReserveError};
+
+    let mut s: InlineString<16> = InlineString::with_capacity(16);
+    s.push_hex(255_u8);
+    s.push_hex_upper_padded(10_u8, 3, '0');
+    s.push_octal(8_u8);
+    s.push_binary_padded(-1_i8, 0, ' ');
+    assert_eq!(s, "ff00A1011111111");
+    assert_eq!(s.try_push_binary(3_u8), Err(CapacityError));
+    assert_eq!(s.try_push_integer_padded(7, 2, '0'), Err(CapacityError));
+    assert_eq!(s.try_push_hex_padded(1_u8, 2, 'é'), Err(CapacityError));
+    assert_eq!(
+        s.try_push_hex_padded(1_u8, usize::MAX, '0'),
+        Err(CapacityError)
+    );
+    assert_eq!(s, "ff00A1011111111");
+
+    s.clear();
+    s.push_integer_padded(-5, 4, '0');
+    s.push_f64(0.5);
+    assert_eq!(s, "-0050.5");
+    assert_eq!(s.try_push_f64(f64::MAX), Err(CapacityError));
+    assert_eq!(s.try_push_f32_fixed(1.0, 9), Err(CapacityError));
+    assert_eq!(s.try_push_f64_fixed(0.1, usize::MAX), Err(CapacityError));
+    s.push_f32_fixed(1.25, 1);
+    assert_eq!(s, "-0050.51.2");
+
+    let mut s: InlineString<2048> = InlineString::new();
+    s.push_f64_fixed(5e-324, 1100);
+    assert_eq!(s.len(), 1102);
+    assert_eq!(s.as_bytes()[1075], b'5');
+    assert!(s.ends_with(&"0".repeat(26)));
+    s.clear();
+    s.push_f64(-f64::MAX);
+    assert_eq!(s.len(), 310);
+    s.clear();
+    s.push_f64_fixed(f64::NAN, 2000);
+    assert_eq!(s, "NaN");
+
+    let mut s: InlineString<8> = InlineString::from_str("hello");
+    assert_eq!(s.try_reserve(3), Ok(()));
+    assert_eq!(
+        s.try_reserve_exact(4),
+        Err(TryReserveError::CapacityOverflow)
+    );
+    s.shrink_to_fit();
+    assert_eq!(s.capacity(), 8);
+
+    s.replace_range(1..4, "EY");
+    assert_eq!(s, "hEYo");
+    assert_eq!(s.try_replace_range(..1, "123456"), Err(CapacityError));
+    assert_eq!(&s[1..3], "EY");
+    s[1..3].make_ascii_lowercase();
+    assert_eq!(s.split_off(2), "yo");
+    let (a, b) = InlineString::<8>::from_str("ab").split_at_owned(1);
+    assert_eq!((a.as_str(), b.as_str()), ("a", "b"));
+
+    let mut s: InlineString<8> = InlineString::from_str(" <x> ");
+    s.trim_in_place();
+    assert!(s.strip_prefix_in_place('<'));
+    assert!(s.strip_suffix_in_place(">"));
+    assert_eq!(s, "x");
+
+    let mut s: InlineString<8> = "ab".chars().collect();
+    s.extend(["c", "d"].iter().copied());
+    s.extend(Some(String::from("e")));
+    s.extend(Some(Cow::Borrowed("f")));
+    assert_eq!(s, String::from("abcdef"));
+    assert_eq!(Cow::Borrowed("abcdef"), s);
+    assert!(s < *"b");
+    assert_eq!(s.clone().into_iter().rev().collect::<String>(), "fedcba");
+    assert_eq!((&s).into_iter().count(), 6);
+    assert_eq!(s.clone().into_string(), "abcdef");
+    assert_eq!(&*s.clone().into_boxed_str(), "abcdef");
+
+    let (buf, len) = s.into_inline_parts();
+    let s = InlineString::<8>::from_buf_and_len(buf, len).unwrap();
+    assert_eq!(s, "abcdef");
+    assert!(InlineString::<2>::from_buf_and_len([0xff, 0], 1).is_err());
+}
+
+#[test]
+#[should_panic]
+fn test_inline_string_panic() {
+    use smallstr::InlineString;
+
+    let mut s: InlineString<2> = InlineString::new();
+
+    s.push_str("abc");
+}
+
+#[test]
+fn test_reserve_exact_and_shrink_to() {
+    let mut s: SmallString<4> = SmallString::from("foo");
+
+    s.reserve_exact(1);
+    assert!(!s.spilled());
+
+    s.reserve_exact(5);
+    assert_eq!(s.capacity(), 8);
+
+    s.reserve_exact(5);
+    assert_eq!(s.capacity(), 8);
+
+    s.push_str("barbaz");
+    let capacity = s.capacity();
+    s.shrink_to(capacity + 1);
+    assert_eq!(s.capacity(), capacity);
+
+    s.shrink_to(10);
+    assert_eq!(s.capacity(), 10);
+
+    s.shrink_to(4);
+    assert_eq!(s.capacity(), 9);
+    assert!(s.spilled());
+
+    s.truncate(3);
+    s.shrink_to(4);
+    assert!(!s.spilled());
+    assert_eq!(s, "foo");
+}
+
+// 清空已溢出到堆上的字符串后再次扩容，不能泄漏原来的堆缓冲区（由 Miri 检查）
+#[test]
+fn test_regrow_emptied_heap() {
+    let long = "abcdefghijklmnop";
+
+    let mut s: SmallString<4> = SmallString::from(long);
+    s.clear();
+    s.push_str(long);
+    s.push_str(long);
+    assert_eq!(s.len(), 32);
+
+    s.truncate(0);
+    s.reserve(100);
+    assert!(s.capacity() >= 100);
+
+    s.grow(50);
+    assert_eq!(s.capacity(), 50);
+
+    s.shrink_to(20);
+    assert_eq!(s.capacity(), 20);
+    assert!(s.is_empty());
+
+    s.insert_str(0, long);
+    s.truncate(0);
+    s.replace_range(.., long);
+    s.truncate(0);
+    s.push_integer(u128::MAX);
+    s.clear();
+    unsafe { s.as_mut_vec() }.extend_from_slice(long.as_bytes());
+    assert_eq!(s, long);
+}
+
+#[test]
+fn test_resize_inline() {
+    let s: SmallString<4> = SmallString::from("foobar");
+    let ptr = s.as_ptr();
+    let capacity = s.capacity();
+
+    let s: SmallString<16> = s.resize_inline();
+    assert!(s.spilled());
+    assert_eq!(s.as_ptr(), ptr);
+    assert_eq!(s.capacity(), capacity);
+    assert_eq!(s, "foobar");
+
+    let s: SmallString<16> = SmallString::from("foo");
+    let s: SmallString<4> = s.resize_inline();
+    assert!(!s.spilled());
+    assert_eq!(s, "foo");
+
+    let s: SmallString<16> = SmallString::from("foobar");
+    let s: SmallString<4> = s.resize_inline();
+    assert!(s.spilled());
+    assert_eq!(s, "foobar");
+}
+
+#[test]
+fn test_cross_size_comparison() {
+    let a: SmallString<4> = SmallString::from("foo");
+    let b: SmallString<32> = SmallString::from("foo");
+    let c: SmallString<1> = SmallString::from("bar");
+
+    assert!(a.eq_any_size(&b));
+    assert!(!a.eq_any_size(&c));
+    assert_eq!(c.cmp_any_size(&a), core::cmp::Ordering::Less);
+    assert_eq!(b.cmp_any_size(&c), core::cmp::Ordering::Greater);
+    assert_eq!(a.cmp_any_size(&b), core::cmp::Ordering::Equal);
+
+    // 同一`SIZE`之间的比较仍然可以推断出右侧的类型
+    let d = SmallString::from("fop");
+    assert!(a < d);
+    assert_eq!(a, SmallString::from("foo"));
+
+    let mut map: hashbrown::HashMap<SmallString<4>, i32> = hashbrown::HashMap::new();
+    map.insert(a, 1);
+    assert_eq!(map.get(b.as_str()), Some(&1));
+    assert_eq!(map.get("foo"), Some(&1));
+    assert_eq!(map.get(c.as_str()), None);
+
+    use core::hash::BuildHasher;
+    let hasher = map.hasher();
+    assert_eq!(hasher.hash_one(&b), hasher.hash_one("foo"));
+}
+
+#[cfg(feature = "hashbrown")]
+#[test]
+fn test_any_size_key() {
+    use smallstr::AnySize;
+
+    let mut set: hashbrown::HashSet<SmallString<4>> = hashbrown::HashSet::new();
+    set.insert(SmallString::from("foo"));
+    set.insert(SmallString::from("barbazqux"));
+
+    let foo: SmallString<32> = SmallString::from("foo");
+    let bar: SmallString<1> = SmallString::from("barbazqux");
+    let baz: SmallString<8> = SmallString::from("baz");
+
+    assert!(set.contains(&AnySize(&foo)));
+    assert!(set.contains(&AnySize(&bar)));
+    assert!(!set.contains(&AnySize(&baz)));
+    assert_eq!(set.get(&AnySize(&foo)).unwrap(), "foo");
+}
+
+#[test]
+fn test_replace_range() {
+    let mut s: SmallString<8> = SmallString::from("foobar");
+
+    s.replace_range(3..3, "-");
+    assert_eq!(s, "foo-bar");
+    assert!(!s.spilled());
+
+    s.replace_range(..3, "α");
+    assert_eq!(s, "α-bar");
+
+    s.replace_range(3.., "baz and more");
+    assert_eq!(s, "α-baz and more");
+    assert!(s.spilled());
+
+    s.replace_range(2..=3, "");
+    assert_eq!(s, "αaz and more");
+
+    s.replace_range(.., "");
+    assert!(s.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_replace_range_panic() {
+    let mut s: SmallString<8> = SmallString::from("αβγ");
+
+    s.replace_range(1..2, "x");
+}
+
+#[test]
+fn test_split_off() {
+    let mut s: SmallString<4> = SmallString::from("foobarbaz");
+
+    let tail = s.split_off(6);
+    assert_eq!(s, "foobar");
+    assert_eq!(tail, "baz");
+    assert!(!tail.spilled());
+
+    let empty = s.split_off(6);
+    assert!(empty.is_empty());
+
+    let (head, tail) = s.split_at_owned(0);
+    assert!(head.is_empty());
+    assert_eq!(tail, "foobar");
+
+    let (head, tail) = tail.split_at_owned(2);
+    assert_eq!(head, "fo");
+    assert_eq!(tail, "obar");
+}
+
+#[test]
+#[should_panic]
+fn test_split_off_panic() {
+    let mut s: SmallString<8> = SmallString::from("αβγ");
+
+    let _ = s.split_off(3);
+}
+
+#[test]
+fn test_replace() {
+    let s: SmallString<8> = SmallString::from("αβ-αβ-αβ");
+
+    assert_eq!(s.replace('-', "+"), "αβ+αβ+αβ");
+    assert_eq!(s.replace("αβ", "x"), "x-x-x");
+    assert_eq!(s.replace(&"β".to_owned(), ""), "α-α-α");
+    assert_eq!(s.replace(['α', '-'], ""), "βββ");
+    assert_eq!(s.replace(&['β'][..], "b"), "αb-αb-αb");
+    assert_eq!(s.replace(|ch: char| !ch.is_ascii(), "?"), "??-??-??");
+    assert_eq!(s.replace("", "."), s.as_str().replace("", "."));
+    assert_eq!(s.replace("none", "x"), s);
+
+    assert_eq!(s.replacen('-', "+", 1), "αβ+αβ-αβ");
+    assert_eq!(s.replacen("", ".", 2), s.as_str().replacen("", ".", 2));
+    assert_eq!(s.replacen('-', "+", 0), s);
+}
+
+#[test]
+fn test_replace_in_place() {
+    let mut s: SmallString<4> = SmallString::from("αβ-αβ-αβ");
+    let ptr = s.as_ptr();
+
+    s.replace_in_place("αβ", "a");
+    assert_eq!(s, "a-a-a");
+    s.replace_in_place('-', "");
+    assert_eq!(s, "aaa");
+    s.replace_in_place(|ch| ch == 'a', "b");
+    assert_eq!(s, "bbb");
+    s.replace_in_place("", "");
+    assert_eq!(s, "bbb");
+    assert_eq!(s.as_ptr(), ptr);
+
+    s.replace_in_place('b', "αβ");
+    assert_eq!(s, "αβαβαβ");
+    s.replace_in_place("", "-");
+    assert_eq!(s, "-α-β-α-β-α-β-");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_replace_in_place_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut s: SmallString<8> = SmallString::from("βxβ");
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        s.replace_in_place(
+            |ch| {
+                assert_ne!(ch, 'x');
+                ch == 'β'
+            },
+            "y",
+        )
+    }));
+
+    assert!(result.is_err());
+    assert!(core::str::from_utf8(s.as_bytes()).is_ok());
+    assert_eq!(s, "yxβ");
+}
+
+/// 返回固定结果的`SmallPattern`，用于检查外部实现返回无效值时不会破坏字符串。
+#[cfg(feature = "std")]
+struct BadPattern {
+    range: (usize, usize),
+    affix: usize,
+    min_len: usize,
+}
+
+#[cfg(feature = "std")]
+impl smallstr::SmallPattern for BadPattern {
+    fn find_in(&mut self, _: &str) -> Option<(usize, usize)> {
+        Some(self.range)
+    }
+
+    fn prefix_len_in(&mut self, _: &str) -> Option<usize> {
+        Some(self.affix)
+    }
+
+    fn suffix_len_in(&mut self, _: &str) -> Option<usize> {
+        Some(self.affix)
+    }
+
+    fn min_len(&self) -> usize {
+        self.min_len
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_replace_in_place_bad_pattern() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    for &(range, to) in [
+        ((0, 1), ""),
+        ((1, 2), ""),
+        ((0, 9), ""),
+        ((2, 1), ""),
+        ((0, 2), "xyz"),
+    ]
+    .iter()
+    {
+        let mut s: SmallString<8> = SmallString::from("é");
+        let pattern = BadPattern {
+            range,
+            affix: 0,
+            min_len: usize::MAX,
+        };
+        let result = catch_unwind(AssertUnwindSafe(|| s.replace_in_place(pattern, to)));
+
+        assert!(result.is_err());
+        assert_eq!(s, "é");
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_strip_in_place_bad_pattern() {
+    use smallstr::InlineString;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    for &affix in [1, 3].iter() {
+        let pattern = || BadPattern {
+            range: (0, 0),
+            affix,
+            min_len: 0,
+        };
+
+        let mut s: SmallString<8> = SmallString::from("é");
+        assert!(catch_unwind(AssertUnwindSafe(|| s.strip_prefix_in_place(pattern()))).is_err());
+        assert!(catch_unwind(AssertUnwindSafe(|| s.strip_suffix_in_place(pattern()))).is_err());
+        assert_eq!(s, "é");
+
+        let mut s: InlineString<8> = InlineString::from_str("é");
+        assert!(catch_unwind(AssertUnwindSafe(|| s.strip_prefix_in_place(pattern()))).is_err());
+        assert!(catch_unwind(AssertUnwindSafe(|| s.strip_suffix_in_place(pattern()))).is_err());
+        assert_eq!(s, "é");
+    }
+}
+
+#[test]
+fn test_case_conversion() {
+    let samples = [
+        "",
+        "Content-Type",
+        "straße",
+        "ὈΔΥΣΣΕΎΣ",
+        "ΣΑΣ Σ",
+        "ΑΣ'. Α.Σ-",
+        "aΣ\u{301}b ΑΣ\u{301}",
+        "İstanbul",
+        "ﬁnal ǅ",
+        "ǰ",
+        "ⓗⓔⓛⓛⓞ ꭰ",
+    ];
+
+    for sample in samples.iter() {
+        let s: SmallString<8> = SmallString::from(*sample);
+
+        assert_eq!(s.to_lowercase_small(), sample.to_lowercase());
+        assert_eq!(s.to_uppercase_small(), sample.to_uppercase());
+
+        let mut lower = s.clone();
+        lower.to_lowercase_in_place();
+        assert_eq!(lower, sample.to_lowercase());
+
+        let mut upper = s.clone();
+        upper.to_uppercase_in_place();
+        assert_eq!(upper, sample.to_uppercase());
+
+        let mut folded = s.clone();
+        folded.case_fold_in_place();
+        assert_eq!(folded, s.case_fold());
+        assert_eq!(s.to_uppercase_small().case_fold(), s.case_fold());
+    }
+
+    for &(upper, lower) in [("ΣΑΣ", "σας"), ("Σ", "σ"), ("ΑΣ.", "ας.")].iter() {
+        let s: SmallString<8> = SmallString::from(upper);
+        assert_eq!(s.to_lowercase_small(), lower);
+        assert!(!s.to_lowercase_small().spilled());
+
+        let mut s = s;
+        let ptr = s.as_ptr();
+        s.to_lowercase_in_place();
+        assert_eq!(s, lower);
+        assert!(!s.spilled());
+        assert_eq!(s.as_ptr(), ptr);
+    }
+
+    let s: SmallString<8> = SmallString::from("Straße");
+    assert_eq!(s.case_fold(), "strasse");
+    assert!(!s.case_fold().spilled());
+
+    let mut s: SmallString<8> = SmallString::from("HEADER");
+    let ptr = s.as_ptr();
+    s.to_lowercase_in_place();
+    assert_eq!(s, "header");
+    assert_eq!(s.as_ptr(), ptr);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_case_fold_idempotent() {
+    let mut buf = [0; 4];
+    for ch in (0..=0x10FFFF).filter_map(char::from_u32) {
+        let s: SmallString<16> = SmallString::from(&*ch.encode_utf8(&mut buf));
+        let folded = s.case_fold();
+        assert_eq!(folded.case_fold(), folded, "{:?}", ch);
+    }
+
+    let sharp: SmallString<8> = SmallString::from("\u{1E9E}");
+    assert_eq!(sharp.case_fold(), "ss");
+    assert_eq!(SmallString::<8>::from("ß").case_fold(), sharp.case_fold());
+    assert_eq!(SmallString::<8>::from("SS").case_fold(), sharp.case_fold());
+}
+
+#[test]
+fn test_trim_in_place() {
+    let mut s: SmallString<4> = SmallString::from("\u{3000} foo bar \t\n");
+    let ptr = s.as_ptr();
+
+    s.trim_end_in_place();
+    assert_eq!(s, "\u{3000} foo bar");
+    s.trim_start_in_place();
+    assert_eq!(s, "foo bar");
+    assert_eq!(s.as_ptr(), ptr);
+
+    let mut s: SmallString<4> = SmallString::from("  ");
+    s.trim_in_place();
+    assert!(s.is_empty());
+}
+
+#[test]
+fn test_strip_in_place() {
+    let mut s: SmallString<8> = SmallString::from("[αβγ]");
+
+    assert!(s.strip_prefix_in_place('['));
+    assert!(s.strip_suffix_in_place("]"));
+    assert_eq!(s, "αβγ");
+
+    assert!(!s.strip_prefix_in_place("β"));
+    assert!(!s.strip_suffix_in_place(['α', 'β']));
+    assert!(s.strip_prefix_in_place(|ch: char| !ch.is_ascii()));
+    assert!(s.strip_suffix_in_place(&['γ'][..]));
+    assert_eq!(s, "β");
+
+    assert!(s.strip_prefix_in_place(""));
+    assert!(s.strip_suffix_in_place(&"β".to_owned()));
+    assert!(s.is_empty());
+    assert!(!s.strip_prefix_in_place('x'));
+}
+
+#[test]
+fn test_into_iter() {
+    let s: SmallString<8> = SmallString::from("aβc𝄞");
+
+    let mut iter = s.clone().into_iter();
+    assert_eq!(iter.next(), Some('a'));
+    assert_eq!(iter.next_back(), Some('𝄞'));
+    assert_eq!(iter.as_str(), "βc");
+    assert_eq!(iter.clone().collect::<String>(), "βc");
+    assert_eq!(iter.next_back(), Some('c'));
+    assert_eq!(iter.next(), Some('β'));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.as_str(), "");
+
+    let mut chars = String::new();
+    for ch in &s {
+        chars.push(ch);
+    }
+    assert_eq!(chars, "aβc𝄞");
+    assert_eq!(s.into_iter().rev().collect::<String>(), "𝄞cβa");
+
+    let s: SmallString<4> = SmallString::from("spilled onto the heap");
+    assert!(s.spilled());
+    assert_eq!(s.into_iter().filter(|&ch| ch != ' ').count(), 18);
+}
+
+#[test]
+fn test_index() {
+    use core::ops::Bound;
+
+    let mut s: SmallString<4> = SmallString::from("aβcdef");
+
+    assert_eq!(&s[..], "aβcdef");
+    assert_eq!(&s[1..3], "β");
+    assert_eq!(&s[1..=3], "βc");
+    assert_eq!(&s[..=2], "aβ");
+    assert_eq!(&s[3..], "cdef");
+    assert_eq!(&s[(Bound::Excluded(0), Bound::Included(3))], "βc");
+
+    s[4..=5].make_ascii_uppercase();
+    assert_eq!(s, "aβcDEf");
+
+    assert_eq!(s.get(1..=3), Some("βc"));
+    assert_eq!(s.get(..2), None);
+    assert_eq!(s.get(4..10), None);
+    s.get_mut(..).unwrap().make_ascii_lowercase();
+    assert_eq!(s, "aβcdef");
+    assert!(s.get_mut(2..).is_none());
+}
+
+#[test]
+#[should_panic]
+fn test_index_panic() {
+    let s: SmallString<4> = SmallString::from("aβc");
+    let _ = &s[..=1];
+}
+
+#[test]
+fn test_from_utf8() {
+    use core::convert::TryFrom;
+    use smallvec::SmallVec;
+
+    let mut buf: SmallVec<u8, 8> = SmallVec::new();
+    buf.extend_from_slice("αβ".as_bytes());
+    let s = SmallString::from_utf8(buf).unwrap();
+    assert_eq!(s, "αβ");
+    assert!(!s.spilled());
+
+    let mut buf: SmallVec<u8, 8> = SmallVec::new();
+    buf.extend_from_slice(b"ab\xffcd");
+    let error = SmallString::from_utf8(buf).unwrap_err();
+    assert_eq!(error.utf8_error().valid_up_to(), 2);
+    assert_eq!(error.as_bytes(), b"ab\xffcd");
+    assert_eq!(&error.into_bytes()[..], b"ab\xffcd");
+
+    let s: SmallString<4> = SmallString::try_from(&b"ok"[..]).unwrap();
+    assert_eq!(s, "ok");
+    assert!(SmallString::<4>::try_from(&b"\xc0"[..]).is_err());
+
+    let vec = b"a heap allocated buffer".to_vec();
+    let ptr = vec.as_ptr();
+    let s = SmallString::<4>::try_from(vec).unwrap();
+    assert_eq!(s, "a heap allocated buffer");
+    assert_eq!(s.as_ptr(), ptr);
+    let error = SmallString::<4>::try_from(b"\xf0\x9f".to_vec()).unwrap_err();
+    assert_eq!(error.utf8_error().error_len(), None);
+}
+
+#[test]
+fn test_from_utf8_lossy() {
+    let s: SmallString<16> = SmallString::from_utf8_lossy(b"Hello \xF0\x90\x80World");
+    assert_eq!(s, "Hello \u{FFFD}World");
+    assert!(!s.spilled());
+
+    let s: SmallString<16> = SmallString::from_utf8_lossy(b"\xffa\xc3\xa9\xc3");
+    assert_eq!(s, "\u{FFFD}aé\u{FFFD}");
+
+    let s: SmallString<16> = SmallString::from_utf8_lossy(b"");
+    assert!(s.is_empty());
+
+    let mut bytes = "αβγ".repeat(20).into_bytes();
+    assert_eq!(SmallString::<16>::from_utf8_lossy(&bytes), "αβγ".repeat(20));
+    bytes[100] = b'\xff';
+    let s: SmallString<16> = SmallString::from_utf8_lossy(&bytes);
+    assert_eq!(s, String::from_utf8_lossy(&bytes));
+}
+
+#[test]
+fn test_from_buf_and_len() {
+    let mut buf = [0; 8];
+    buf[..3].copy_from_slice(b"abc");
+
+    let s = SmallString::from_buf_and_len(buf, 3).unwrap();
+    assert_eq!(s, "abc");
+    assert_eq!(s.into_inline_parts(), Ok((buf, 3)));
+
+    buf[3] = 0xff;
+    assert!(SmallString::from_buf_and_len(buf, 4).is_err());
+    assert_eq!(SmallString::from_buf_and_len(buf, 0).unwrap(), "");
+
+    const EMPTY: SmallString<8> = unsafe { SmallString::from_buf_and_len_unchecked([0; 8], 0) };
+    assert!(EMPTY.is_empty());
+
+    let s: SmallString<8> = SmallString::from("exactly8");
+    assert_eq!(s.clone().into_inline_parts(), Ok((*b"exactly8", 8)));
+    assert_eq!(s.clone().into_inner(), Ok(*b"exactly8"));
+
+    let mut s: SmallString<8> = SmallString::from("spilled beyond eight");
+    assert_eq!(s.clone().into_inline_parts(), Err(s.clone()));
+    s.truncate(7);
+    assert!(s.spilled());
+    assert_eq!(s.into_inline_parts(), Ok((*b"spilled\0", 7)));
+}
+
+#[test]
+#[should_panic]
+fn test_from_buf_and_len_panic() {
+    let _ = SmallString::from_buf_and_len([b'a'; 4], 5);
+}
+
+#[test]
+fn test_utf8_error_positions() {
+    use core::{convert::TryFrom, str};
+
+    let mut bytes = "αβγ abcdefgh ".repeat(20).into_bytes();
+    assert!(SmallString::<16>::try_from(&bytes[..]).is_ok());
+
+    for &(at, bad) in &[
+        (0, &b"\xff"[..]),
+        (37, b"\xc3("),
+        (150, b"\xe2\x82"),
+        (200, b"\xf0\x9f\x98"),
+    ] {
+        let mut input = bytes.clone();
+        input.splice(at..at, bad.iter().copied());
+        let expected = str::from_utf8(&input).unwrap_err();
+        assert_eq!(SmallString::<16>::try_from(&input[..]), Err(expected));
+
+        let mut buf = [b' '; 256];
+        let len = input.len().min(256);
+        buf[..len].copy_from_slice(&input[..len]);
+        assert_eq!(
+            SmallString::from_buf_and_len(buf, len),
+            Err(str::from_utf8(&buf[..len]).unwrap_err())
+        );
+    }
+
+    bytes.push(0x80);
+    let error = SmallString::<16>::try_from(bytes.clone()).unwrap_err();
+    assert_eq!(error.utf8_error(), str::from_utf8(&bytes).unwrap_err());
+}
+
+#[test]
+fn test_utf16() {
+    use smallstr::FromUtf16Error;
+
+    let text = "𝄞music ünïcode";
+    let units: alloc::vec::Vec<u16> = text.encode_utf16().collect();
+
+    let s: SmallString<32> = SmallString::from_utf16(&units).unwrap();
+    assert_eq!(s, text);
+    assert!(!s.spilled());
+
+    let encoded = s.to_utf16_small::<32>();
+    assert_eq!(&encoded[..], &units[..]);
+    assert!(!encoded.spilled());
+    assert!(s.to_utf16_small::<4>().spilled());
+
+    let le: alloc::vec::Vec<u8> = units.iter().flat_map(|u| u.to_le_bytes()).collect();
+    let be: alloc::vec::Vec<u8> = units.iter().flat_map(|u| u.to_be_bytes()).collect();
+    assert_eq!(SmallString::<32>::from_utf16le_bytes(&le).unwrap(), text);
+    assert_eq!(SmallString::<32>::from_utf16be_bytes(&be).unwrap(), text);
+    let error = SmallString::<32>::from_utf16le_bytes(&le[1..]).unwrap_err();
+    assert_eq!(error, FromUtf16Error::OddLength);
+    assert_eq!(error.to_string(), "invalid utf-16: odd number of bytes");
+
+    let bad = [0xD834, 0x0061, 0xDD1E, 0x0062];
+    let error = SmallString::<32>::from_utf16(&bad).unwrap_err();
+    assert_eq!(error, FromUtf16Error::LoneSurrogate);
+    assert_eq!(error.to_string(), "invalid utf-16: lone surrogate found");
+    assert_eq!(
+        SmallString::<32>::from_utf16_lossy(&bad),
+        "\u{FFFD}a\u{FFFD}b"
+    );
+    assert_eq!(
+        SmallString::<32>::from_utf16be_bytes(&[0xD8, 0x34]),
+        Err(FromUtf16Error::LoneSurrogate)
+    );
+}
@@ -0,0 +1,218 @@
+//! 生成`src/float/table.rs`中 Ryu 算法使用的5的幂表。
+//!
+//! 不依赖任何外部 crate，在仓库根目录运行：
+//!
+//! ```text
+//! rustc -O tools/ryu_table.rs -o target/ryu_table
+//! target/ryu_table > src/float/table.rs
+//! ```
+//!
+//! 表的定义与 Ryu 参考实现（<https://github.com/ulfjack/ryu>）中的
+//! `DOUBLE_POW5_INV_SPLIT`和`DOUBLE_POW5_SPLIT`相同，只是每一项存放在一个`u128`中。
+
+use std::cmp::Ordering;
+
+/// `src/float.rs`中的`POW5_INV_BITCOUNT`和`POW5_BITCOUNT`。
+const BITCOUNT: u32 = 125;
+
+const INV_TABLE_SIZE: u32 = 342;
+const TABLE_SIZE: u32 = 326;
+
+/// 使用小端序64位limb的无符号大整数，只实现生成表所需的运算。
+#[derive(Clone)]
+struct Big(Vec<u64>);
+
+impl Big {
+    fn from_u64(n: u64) -> Big {
+        Big(vec![n])
+    }
+
+    fn pow5(e: u32) -> Big {
+        let mut n = Big::from_u64(1);
+        for _ in 0..e {
+            n.mul_small(5);
+        }
+        n
+    }
+
+    fn pow2(e: u32) -> Big {
+        Big::from_u64(1).shl(e)
+    }
+
+    fn trim(mut self) -> Big {
+        while self.0.len() > 1 && *self.0.last().unwrap() == 0 {
+            self.0.pop();
+        }
+        self
+    }
+
+    fn bits(&self) -> u32 {
+        match self.0.iter().rposition(|&limb| limb != 0) {
+            Some(i) => i as u32 * 64 + 64 - self.0[i].leading_zeros(),
+            None => 0,
+        }
+    }
+
+    fn bit(&self, i: u32) -> bool {
+        self.0
+            .get((i / 64) as usize)
+            .map_or(false, |limb| limb >> (i % 64) & 1 == 1)
+    }
+
+    fn mul_small(&mut self, m: u64) {
+        let mut carry = 0;
+        for limb in &mut self.0 {
+            let product = *limb as u128 * m as u128 + carry;
+            *limb = product as u64;
+            carry = product >> 64;
+        }
+        if carry > 0 {
+            self.0.push(carry as u64);
+        }
+    }
+
+    fn add_small(&mut self, m: u64) {
+        let mut carry = m;
+        for limb in &mut self.0 {
+            let (sum, overflow) = limb.overflowing_add(carry);
+            *limb = sum;
+            carry = overflow as u64;
+            if carry == 0 {
+                return;
+            }
+        }
+        self.0.push(carry);
+    }
+
+    fn shl(&self, n: u32) -> Big {
+        let mut limbs = vec![0; (n / 64) as usize];
+        let shift = n % 64;
+        let mut carry = 0;
+        for &limb in &self.0 {
+            limbs.push(limb << shift | carry);
+            carry = if shift == 0 { 0 } else { limb >> (64 - shift) };
+        }
+        limbs.push(carry);
+        Big(limbs).trim()
+    }
+
+    fn shr(&self, n: u32) -> Big {
+        let skip = (n / 64) as usize;
+        let shift = n % 64;
+        let limbs = self.0.get(skip..).unwrap_or(&[]);
+        let mut result = Vec::with_capacity(limbs.len());
+        for (i, &limb) in limbs.iter().enumerate() {
+            let high = match limbs.get(i + 1) {
+                Some(&next) if shift > 0 => next << (64 - shift),
+                _ => 0,
+            };
+            result.push(limb >> shift | high);
+        }
+        if result.is_empty() {
+            result.push(0);
+        }
+        Big(result).trim()
+    }
+
+    fn cmp(&self, other: &Big) -> Ordering {
+        let len = self.0.len().max(other.0.len());
+        for i in (0..len).rev() {
+            let a = self.0.get(i).copied().unwrap_or(0);
+            let b = other.0.get(i).copied().unwrap_or(0);
+            match a.cmp(&b) {
+                Ordering::Equal => {}
+                ordering => return ordering,
+            }
+        }
+        Ordering::Equal
+    }
+
+    /// `self -= other`，要求`self >= other`。
+    fn sub(&mut self, other: &Big) {
+        let mut borrow = false;
+        for i in 0..self.0.len() {
+            let b = other.0.get(i).copied().unwrap_or(0);
+            let (diff, o1) = self.0[i].overflowing_sub(b);
+            let (diff, o2) = diff.overflowing_sub(borrow as u64);
+            self.0[i] = diff;
+            borrow = o1 || o2;
+        }
+        assert!(!borrow);
+    }
+
+    /// 逐位长除法，返回`floor(self / divisor)`。
+    fn div(&self, divisor: &Big) -> Big {
+        let mut quotient = Big(vec![0; self.0.len()]);
+        let mut remainder = Big::from_u64(0);
+        for i in (0..self.bits()).rev() {
+            remainder = remainder.shl(1);
+            if self.bit(i) {
+                remainder.0[0] |= 1;
+            }
+            if remainder.cmp(divisor) != Ordering::Less {
+                remainder.sub(divisor);
+                quotient.0[(i / 64) as usize] |= 1 << (i % 64);
+            }
+        }
+        quotient.trim()
+    }
+
+    fn to_u128(&self) -> u128 {
+        assert!(self.bits() <= 128);
+        let low = self.0[0] as u128;
+        let high = self.0.get(1).copied().unwrap_or(0) as u128;
+        high << 64 | low
+    }
+}
+
+/// 与`src/float.rs`中的`pow5bits`相同：`ceil(log2(5^e))`，`e == 0`时为`1`。
+fn pow5bits(e: u32) -> u32 {
+    ((e * 1217359) >> 19) + 1
+}
+
+fn emit(doc: &str, name: &str, values: &[u128]) {
+    println!("/// {}", doc);
+    println!("pub(super) static {}: [u128; {}] = [", name, values.len());
+    for value in values {
+        println!("    0x{:032x},", value);
+    }
+    println!("];");
+}
+
+fn main() {
+    let inv: Vec<u128> = (0..INV_TABLE_SIZE)
+        .map(|q| {
+            let k = BITCOUNT + pow5bits(q) - 1;
+            let mut value = Big::pow2(k).div(&Big::pow5(q));
+            value.add_small(1);
+            value.to_u128()
+        })
+        .collect();
+
+    let pow: Vec<u128> = (0..TABLE_SIZE)
+        .map(|i| {
+            let pow5 = Big::pow5(i);
+            let bits = pow5.bits();
+            let value = if bits <= BITCOUNT {
+                pow5.shl(BITCOUNT - bits)
+            } else {
+                pow5.shr(bits - BITCOUNT)
+            };
+            value.to_u128()
+        })
+        .collect();
+
+    println!("//! Ryu 算法使用的5的幂表，由`tools/ryu_table.rs`生成，请勿手动修改。");
+    println!();
+    emit(
+        "`floor(2^(pow5bits(q) - 1 + 125) / 5^q) + 1`，其中`q`为下标。",
+        "DOUBLE_POW5_INV_SPLIT",
+        &inv,
+    );
+    println!();
+    emit(
+        "`5^i`缩放到恰好125位（向下取整），其中`i`为下标。",
+        "DOUBLE_POW5_SPLIT",
+        &pow,
+    );
+}